@@ -0,0 +1,12 @@
+#version 330
+
+in vec2 fragTexCoord;
+
+out vec4 outColor;
+
+uniform sampler2D uTexture;
+
+void main()
+{
+	outColor = texture(uTexture, fragTexCoord);
+}
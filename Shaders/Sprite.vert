@@ -0,0 +1,17 @@
+#version 330
+
+// World transform and view-projection, uploaded row-major (vectors are rows)
+uniform mat4 uWorldTransform;
+uniform mat4 uViewProj;
+
+layout(location = 0) in vec3 inPosition;
+layout(location = 1) in vec2 inTexCoord;
+
+out vec2 fragTexCoord;
+
+void main()
+{
+	vec4 pos = vec4(inPosition, 1.0);
+	gl_Position = pos * uWorldTransform * uViewProj;
+	fragTexCoord = inTexCoord;
+}
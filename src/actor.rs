@@ -1,9 +1,10 @@
-use std::cell::RefCell;
-use std::rc::Rc;
-
-use crate::component::Component;
+use crate::component::{AsAny, ComponentId};
 use crate::game::Game;
-use crate::math::{Matrix4, Vector2, Vector3};
+use crate::input_map::InputState;
+use crate::math::{math, Matrix4, Vector2, Vector3};
+use crate::registry::Handle;
+
+pub type ActorId = Handle<Actor>;
 
 #[derive(Clone)]
 pub enum State {
@@ -12,72 +13,112 @@ pub enum State {
     Dead,
 }
 
-#[derive(Clone)]
+// What a subclass of Actor would override: logic specific to one kind of
+// actor (ship, laser...), run after the actor's components.
+pub trait ActorBehavior: AsAny + 'static {
+    fn update_actor(&mut self, _game: &mut Game, _id: ActorId, _delta_time: f32) {}
+
+    fn actor_input(&mut self, _game: &mut Game, _id: ActorId, _input: &InputState) {}
+}
+
 pub struct Actor {
     state: State,
     position: Vector2,
     scale: f32,
     rotation: f32,
-    game: Rc<RefCell<Game>>,
     recompute_world_transform: bool,
-    components: Vec<Rc<RefCell<dyn Component>>>,
+    // Sorted by update order
+    components: Vec<(ComponentId, i32)>,
     world_transform: Matrix4,
+    // Position and rotation before the last step, for render interpolation
+    previous_transform: Option<(Vector2, f32)>,
+    behavior: Option<Box<dyn ActorBehavior>>,
+    // Pushed apart and bounced by the collision world on contact
+    collision_response: bool,
 }
 
 impl Actor {
-    pub fn new(game: Rc<RefCell<Game>>) -> Self {
-        let actor = Actor {
+    pub fn new(game: &mut Game) -> ActorId {
+        game.add_actor(Actor {
             state: State::Active,
             position: Vector2::zero(),
             scale: 1.0,
             rotation: 0.0,
-            game,
             recompute_world_transform: true,
             components: Vec::new(),
             world_transform: Matrix4::identity(),
+            previous_transform: None,
+            behavior: None,
+            collision_response: false,
+        })
+    }
+
+    // The actor is looked up again after each component runs, since a
+    // component may have moved, killed or removed it
+    pub fn update(game: &mut Game, id: ActorId, delta_time: f32) {
+        let components = match game.get_actor_mut(id) {
+            Some(actor) if matches!(actor.get_state(), State::Active) => {
+                actor.previous_transform = Some((actor.position, actor.rotation));
+                actor.compute_world_transform();
+                actor.get_components()
+            }
+            _ => return,
         };
-        actor.game.borrow_mut().add_actor(Rc::new(RefCell::new(actor.clone())));
-        actor
-    }
 
-    pub fn update(&mut self, delta_time: f32) {
-        if let State::Active = self.state {
-            self.compute_world_transform();
-            self.update_components(delta_time);
-            self.update_actor(delta_time);
-            self.compute_world_transform();
+        for comp in components {
+            game.with_component(comp, |comp, game| comp.update(game, delta_time));
         }
-    }
 
-    fn update_components(&mut self, delta_time: f32) {
-        for comp in &self.components {
-            comp.borrow_mut().update(delta_time);
+        Actor::with_behavior(game, id, |behavior, game| behavior.update_actor(game, id, delta_time));
+
+        if let Some(actor) = game.get_actor_mut(id) {
+            actor.compute_world_transform();
         }
     }
 
-    fn update_actor(&mut self, _delta_time: f32) {
-        // Actor-specific logic to be implemented by subclasses
+    pub fn process_input(game: &mut Game, id: ActorId, input: &InputState) {
+        let components = match game.get_actor(id) {
+            Some(actor) if matches!(actor.get_state(), State::Active) => actor.get_components(),
+            _ => return,
+        };
+
+        for comp in components {
+            game.with_component(comp, |comp, game| comp.process_input(game, input));
+        }
+
+        Actor::with_behavior(game, id, |behavior, game| behavior.actor_input(game, id, input));
     }
 
-    pub fn process_input(&mut self, key_state: &[u8]) {
-        if let State::Active = self.state {
-            for comp in &self.components {
-                comp.borrow_mut().process_input(key_state);
-            }
-            self.actor_input(key_state);
+    // The behavior is taken out of the actor while it runs, the same way
+    // components are checked out of the game
+    fn with_behavior(
+        game: &mut Game,
+        id: ActorId,
+        f: impl FnOnce(&mut dyn ActorBehavior, &mut Game),
+    ) {
+        let Some(mut behavior) = game.get_actor_mut(id).and_then(|actor| actor.behavior.take()) else {
+            return;
+        };
+        f(behavior.as_mut(), game);
+        if let Some(actor) = game.get_actor_mut(id) {
+            actor.behavior.get_or_insert(behavior);
         }
     }
 
-    fn actor_input(&mut self, _key_state: &[u8]) {
-        // Actor-specific input logic to be implemented by subclasses
+    pub fn set_behavior(&mut self, behavior: Box<dyn ActorBehavior>) {
+        self.behavior = Some(behavior);
     }
 
-    fn compute_world_transform(&mut self) {
+    pub fn get_behavior<T: ActorBehavior>(&self) -> Option<&T> {
+        self.behavior
+            .as_ref()
+            .and_then(|behavior| behavior.as_ref().as_any().downcast_ref())
+    }
+
+    pub fn compute_world_transform(&mut self) {
         if self.recompute_world_transform {
             self.recompute_world_transform = false;
-            self.world_transform = Matrix4::create_scale(self.scale, self.scale, self.scale)
-                * Matrix4::create_rotation_z(self.rotation)
-                * Matrix4::create_translation(Vector3::new(self.position.x, self.position.y, 0.0));
+            self.world_transform = self.make_world_transform(self.position, self.rotation);
 
             // for comp in &self.components {
             //     comp.borrow_mut().on_update_world_transform();
@@ -85,22 +126,52 @@ impl Actor {
         }
     }
 
-    pub fn add_component(&mut self, component: Rc<RefCell<dyn Component>>) {
-        let my_order = component.borrow().get_update_order();
+    /// World transform `alpha` of the way from before the last step to now,
+    /// so rendering between two steps doesn't stutter.
+    pub fn get_interpolated_world_transform(&self, alpha: f32) -> Matrix4 {
+        let Some((position, rotation)) = self.previous_transform else {
+            return self.make_world_transform(self.position, self.rotation);
+        };
+        // The short way round, so crossing ±π doesn't spin the sprite
+        let turn = (self.rotation - rotation + math::PI).rem_euclid(math::TWO_PI) - math::PI;
+        self.make_world_transform(
+            position + (self.position - position) * alpha,
+            rotation + turn * alpha,
+        )
+    }
+
+    // After a jump (wrapping, respawning), draw the new place right away
+    // instead of sliding there
+    pub fn reset_interpolation(&mut self) {
+        self.previous_transform = None;
+    }
+
+    fn make_world_transform(&self, position: Vector2, rotation: f32) -> Matrix4 {
+        Matrix4::create_scale(self.scale, self.scale, self.scale)
+            * Matrix4::create_rotation_z(rotation)
+            * Matrix4::create_translation(Vector3::new(position.x, position.y, 0.0))
+    }
+
+    // Only keeps the bookkeeping; use Game::add_component to register one
+    pub fn add_component(&mut self, component: ComponentId, update_order: i32) {
         let index = self
             .components
             .iter()
-            .position(|comp| comp.borrow().get_update_order() > my_order)
+            .position(|&(_, order)| order > update_order)
             .unwrap_or(self.components.len());
-        self.components.insert(index, component);
+        self.components.insert(index, (component, update_order));
     }
 
-    pub fn remove_component(&mut self, component: Rc<RefCell<dyn Component>>) {
-        if let Some(pos) = self.components.iter().position(|c| Rc::ptr_eq(c, &component)) {
+    pub fn remove_component(&mut self, component: ComponentId) {
+        if let Some(pos) = self.components.iter().position(|&(c, _)| c == component) {
             self.components.remove(pos);
         }
     }
 
+    pub fn get_components(&self) -> Vec<ComponentId> {
+        self.components.iter().map(|&(component, _)| component).collect()
+    }
+
     pub fn set_position(&mut self, pos: Vector2) {
         self.position = pos;
         self.recompute_world_transform = true;
@@ -144,17 +215,16 @@ impl Actor {
         self.state = state;
     }
 
-    pub fn get_game(&self) -> Rc<RefCell<Game>> {
-        Rc::clone(&self.game)
+    pub fn is_dead(&self) -> bool {
+        matches!(self.state, State::Dead)
     }
-}
 
-impl Drop for Actor {
-    fn drop(&mut self) {
-        let self_as_rc = Rc::new(RefCell::new(self.clone()));
-        self.game.borrow_mut().remove_actor(Rc::clone(&self_as_rc));
-        while let Some(component) = self.components.pop() {
-            drop(component);
-        }
+    pub fn has_collision_response(&self) -> bool {
+        self.collision_response
+    }
+
+    // Only contacts between two actors with response on are resolved
+    pub fn set_collision_response(&mut self, enabled: bool) {
+        self.collision_response = enabled;
     }
 }
@@ -1,61 +1,292 @@
-use crate::actor::Actor;
-use crate::circle_component::CircleComponent;
+use crate::actor::{Actor, ActorId, State};
+use crate::collider_component::{ColliderComponent, ColliderShape};
+use crate::collision_world::layers;
+use crate::component::ComponentId;
 use crate::game::Game;
-// use crate::move_component::MoveComponent;
-use crate::random::Random;
+use crate::move_component::MoveComponent;
 use crate::sprite_component::SpriteComponent;
-use crate::math::Vector2;
-use std::rc::Rc;
-use std::cell::RefCell;
+use crate::math::{math, Vector2};
 
-#[derive(Clone)]
+const ASTEROID_RADIUS: f32 = 40.0;
+const ASTEROID_CORNERS: usize = 8;
+
+// Ordered smallest first so sizes can be compared
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AsteroidSize {
+    Small,
+    Medium,
+    Large,
+}
+
+impl AsteroidSize {
+    pub fn get_scale(self) -> f32 {
+        match self {
+            AsteroidSize::Small => 0.35,
+            AsteroidSize::Medium => 0.6,
+            AsteroidSize::Large => 1.0,
+        }
+    }
+
+    pub fn smaller(self) -> Option<AsteroidSize> {
+        match self {
+            AsteroidSize::Small => None,
+            AsteroidSize::Medium => Some(AsteroidSize::Small),
+            AsteroidSize::Large => Some(AsteroidSize::Medium),
+        }
+    }
+}
+
+/// How asteroids move and break apart. Set per level with
+/// `Game::set_split_rules`.
+#[derive(Clone, Debug)]
+pub struct SplitRules {
+    /// Forward speed of a freshly spawned large asteroid.
+    pub base_speed: f32,
+    /// Number of fragments a destroyed asteroid breaks into.
+    pub fragments: u32,
+    /// Angle (radians) the fragments' headings are fanned out over.
+    pub spread: f32,
+    /// Fragment speed relative to the asteroid that broke.
+    pub speed_multiplier: f32,
+    /// Asteroids of this size or smaller vanish instead of splitting.
+    pub smallest: AsteroidSize,
+    /// Asteroids bounce off each other instead of passing through.
+    pub bounce: bool,
+    /// Bounciness when `bounce` is on, 0 to 1 (elastic).
+    pub restitution: f32,
+}
+
+impl Default for SplitRules {
+    fn default() -> Self {
+        SplitRules {
+            base_speed: 150.0,
+            fragments: 2,
+            spread: math::PI_OVER_2,
+            speed_multiplier: 1.5,
+            smallest: AsteroidSize::Small,
+            bounce: false,
+            restitution: 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
 pub struct Asteroid {
-    actor: Actor,
-    circle: Option<Rc<CircleComponent>>,
+    actor: ActorId,
+    collider: ComponentId,
+    move_component: ComponentId,
+    size: AsteroidSize,
 }
 
 impl Asteroid {
-    pub fn new(game: Rc<RefCell<Game>>) ->  Rc<RefCell<Self>> {
-        let asteroid = Rc::new(
-            RefCell::new(Self {
-                actor: Actor::new(game.clone()),
-                circle: None,
-            }));
-
+    pub fn new(game: &mut Game) -> Asteroid {
         // Initialize to random position/orientation
-        let rand_pos = Random::get_vector2(Vector2::new(-512.0, -384.0), Vector2::new(512.0, 384.0));
-        asteroid.borrow_mut().actor.set_position(rand_pos);
+        let bounds = *game.get_world_bounds();
+        let random = game.get_random_mut();
+        let rand_pos = random.get_vector2(bounds.get_min(), bounds.get_max());
+        let rotation = random.get_float_range(0.0, math::TWO_PI);
+        let speed = game.get_split_rules().base_speed;
+        Asteroid::with_size(game, AsteroidSize::Large, rand_pos, rotation, speed)
+    }
 
-        asteroid.borrow_mut().actor.set_rotation(Random::get_float_range(0.0, std::f32::consts::PI * 2.0));
+    pub fn with_size(
+        game: &mut Game,
+        size: AsteroidSize,
+        position: Vector2,
+        rotation: f32,
+        speed: f32,
+    ) -> Asteroid {
+        let actor = Actor::new(game);
+        if let Some(asteroid_actor) = game.get_actor_mut(actor) {
+            asteroid_actor.set_position(position);
+            asteroid_actor.set_rotation(rotation);
+            asteroid_actor.set_scale(size.get_scale());
+        }
 
         // Create a sprite component
-        let rc_asteroid_actor = Rc::new(RefCell::new(asteroid.borrow().actor.clone()));
-        let mut sprite_component = SpriteComponent::new(rc_asteroid_actor.clone(), 100);
-        sprite_component.set_texture(game.borrow_mut().get_texture("Assets/Asteroid.png").unwrap());
+        let sprite_component = SpriteComponent::new(game, actor, 100);
+        // Headless games have no textures to hand out
+        if let Some(texture) = game.get_texture("Assets/Asteroid.png") {
+            if let Some(sprite) = game.get_component_mut::<SpriteComponent>(sprite_component) {
+                sprite.set_texture(texture);
+            }
+        }
+
+        let rules = game.get_split_rules().clone();
 
         // Create a move component, and set a forward speed
-        // let mut move_component = MoveComponent::new(rc_asteroid_actor.clone(), 150);
-        // move_component.set_forward_speed(150.0);
+        let move_component = MoveComponent::new(game, actor, 10);
+        if let Some(move_comp) = game.get_component_mut::<MoveComponent>(move_component) {
+            move_comp.set_forward_speed(speed);
+            // Bigger rocks are heavier
+            move_comp.set_mass(size.get_scale() * size.get_scale());
+            move_comp.set_restitution(rules.restitution);
+        }
 
-        // Create a circle component (for collision)
-        let mut circle_component = CircleComponent::new(rc_asteroid_actor.clone());
-        circle_component.set_radius(40.0);
-        asteroid.borrow_mut().circle = Some(Rc::new(circle_component));
+        // Create a collider with a rough outline; it scales with the actor
+        let outline = Asteroid::random_outline(game);
+        let collider = ColliderComponent::new(game, actor, outline);
+        if let Some(collider_component) = game.get_component_mut::<ColliderComponent>(collider) {
+            collider_component.set_layer(layers::ASTEROID);
+            if rules.bounce {
+                collider_component.set_mask(layers::SHIP | layers::LASER | layers::ASTEROID);
+            } else {
+                // Asteroids pass through each other
+                collider_component.set_mask(layers::SHIP | layers::LASER);
+            }
+        }
+        if let Some(asteroid_actor) = game.get_actor_mut(actor) {
+            asteroid_actor.set_collision_response(rules.bounce);
+        }
 
         // Add to mAsteroids in game
-        game.borrow_mut().add_asteroid(asteroid.clone());
+        let asteroid = Asteroid {
+            actor,
+            collider,
+            move_component,
+            size,
+        };
+        game.add_asteroid(asteroid);
 
         asteroid
     }
 
-    pub fn get_circle(&self) -> Option<Rc<CircleComponent>> {
-        self.circle.clone()
+    // Corners at 0.75 to 1 of the radius: a corner would have to dip below
+    // cos(2π/8) ≈ 0.71 of its neighbours to make the outline concave
+    fn random_outline(game: &mut Game) -> ColliderShape {
+        let random = game.get_random_mut();
+        let vertices = (0..ASTEROID_CORNERS)
+            .map(|i| {
+                let angle = math::TWO_PI * i as f32 / ASTEROID_CORNERS as f32;
+                let radius = ASTEROID_RADIUS * random.get_float_range(0.75, 1.0);
+                Vector2::rotate(Vector2::UNIT_X, angle) * radius
+            })
+            .collect();
+        ColliderShape::Polygon { vertices }
+    }
+
+    /// Mark the asteroid dead and break it into smaller fragments according
+    /// to the game's split rules. Returns the fragments.
+    pub fn destroy(&self, game: &mut Game) -> Vec<Asteroid> {
+        let Some(asteroid_actor) = game.get_actor_mut(self.actor) else {
+            return Vec::new();
+        };
+        if asteroid_actor.is_dead() {
+            return Vec::new();
+        }
+        asteroid_actor.set_state(State::Dead);
+        let position = asteroid_actor.get_position();
+        let rotation = asteroid_actor.get_rotation();
+        game.play_sound("Assets/Explosion.wav");
+
+        let rules = game.get_split_rules().clone();
+        let fragment_size = match self.size.smaller() {
+            Some(size) if self.size > rules.smallest => size,
+            _ => return Vec::new(),
+        };
+        let speed = game
+            .get_component::<MoveComponent>(self.move_component)
+            .map_or(rules.base_speed, |move_comp| move_comp.get_forward_speed())
+            * rules.speed_multiplier;
+
+        (0..rules.fragments)
+            .map(|i| {
+                // Fan the headings out evenly around the original one
+                let offset = if rules.fragments > 1 {
+                    rules.spread * (i as f32 / (rules.fragments - 1) as f32 - 0.5)
+                } else {
+                    0.0
+                };
+                Asteroid::with_size(game, fragment_size, position, rotation + offset, speed)
+            })
+            .collect()
+    }
+
+    pub fn get_actor(&self) -> ActorId {
+        self.actor
+    }
+
+    pub fn get_collider(&self) -> ComponentId {
+        self.collider
+    }
+
+    pub fn get_size(&self) -> AsteroidSize {
+        self.size
     }
 }
 
-impl Drop for Asteroid {
-    fn drop(&mut self) {
-        let asteroid = Rc::new(RefCell::new(self.clone()));
-        self.actor.get_game().borrow_mut().remove_asteroid(asteroid);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::GameConfig;
+
+    fn headless_game() -> Game {
+        Game::with_config(GameConfig::headless())
+    }
+
+    #[test]
+    fn destroyed_asteroid_splits_into_faster_smaller_fragments() {
+        let mut game = headless_game();
+        let position = Vector2::new(200.0, 200.0);
+        let asteroid = Asteroid::with_size(&mut game, AsteroidSize::Large, position, 1.0, 100.0);
+
+        let fragments = asteroid.destroy(&mut game);
+        assert_eq!(fragments.len(), 2);
+        assert!(game.get_actor(asteroid.get_actor()).unwrap().is_dead());
+
+        let mut rotations = Vec::new();
+        for fragment in &fragments {
+            assert_eq!(fragment.get_size(), AsteroidSize::Medium);
+            let actor = game.get_actor(fragment.get_actor()).unwrap();
+            assert_eq!(actor.get_position(), position);
+            assert_eq!(actor.get_scale(), AsteroidSize::Medium.get_scale());
+            rotations.push(actor.get_rotation());
+
+            let move_comp = game.get_component::<MoveComponent>(fragment.move_component).unwrap();
+            assert!((move_comp.get_forward_speed() - 150.0).abs() < 1e-3);
+        }
+        assert!((rotations[1] - rotations[0] - math::PI_OVER_2).abs() < 1e-3);
+
+        // Destroying it twice doesn't split it again
+        assert!(asteroid.destroy(&mut game).is_empty());
+        game.update_game(0.01);
+        assert_eq!(game.get_asteroids().len(), 2);
+    }
+
+    #[test]
+    fn outlines_are_convex_and_inside_the_sprite() {
+        let mut game = headless_game();
+        for _ in 0..50 {
+            let ColliderShape::Polygon { vertices } = Asteroid::random_outline(&mut game) else {
+                panic!("asteroids use a polygon outline");
+            };
+            for (i, &v) in vertices.iter().enumerate() {
+                assert!(v.length() <= ASTEROID_RADIUS + 1e-3);
+                let next = vertices[(i + 1) % vertices.len()];
+                let after = vertices[(i + 2) % vertices.len()];
+                assert!(Vector2::cross(next - v, after - next) > 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn smallest_asteroids_vanish_per_level_rules() {
+        let mut game = headless_game();
+        game.set_split_rules(SplitRules {
+            fragments: 3,
+            smallest: AsteroidSize::Medium,
+            ..SplitRules::default()
+        });
+
+        let large = Asteroid::with_size(&mut game, AsteroidSize::Large, Vector2::zero(), 0.0, 100.0);
+        let fragments = large.destroy(&mut game);
+        assert_eq!(fragments.len(), 3);
+
+        assert!(fragments[0].destroy(&mut game).is_empty());
+
+        let small = Asteroid::with_size(&mut game, AsteroidSize::Small, Vector2::zero(), 0.0, 100.0);
+        assert!(small.destroy(&mut game).is_empty());
+        game.update_game(0.01);
+        assert_eq!(game.get_asteroids().len(), 2);
     }
 }
@@ -0,0 +1,470 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use sdl2::audio::{AudioCallback, AudioDevice, AudioSpecDesired};
+use sdl2::Sdl;
+
+/// Everything is mixed at this rate, in interleaved stereo f32.
+pub const SAMPLE_RATE: u32 = 44100;
+pub const CHANNELS: usize = 2;
+
+/// Volume groups; each has its own volume.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Channel {
+    Effects,
+    Music,
+}
+
+/// Where the mixed audio goes.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AudioOutput {
+    /// The default sound device through SDL.
+    Sdl,
+    /// Mix in step with the game and throw the result away (headless, CI).
+    Null,
+    /// Mix in step with the game and write the result to a WAV file, so
+    /// tests can check what would have been heard.
+    WaveFile(PathBuf),
+}
+
+/// Decoded audio, converted to the mixer's rate and stereo on load.
+#[derive(Debug, PartialEq)]
+pub struct Sound {
+    samples: Vec<f32>,
+}
+
+impl Sound {
+    pub fn load(file_name: impl AsRef<Path>) -> Result<Sound, String> {
+        let file_name = file_name.as_ref();
+        let error = |err: hound::Error| format!("Failed to load sound {}: {}", file_name.display(), err);
+        let mut reader = hound::WavReader::open(file_name).map_err(error)?;
+        let spec = reader.spec();
+        let samples = match spec.sample_format {
+            hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<Vec<_>, _>>(),
+            hound::SampleFormat::Int => {
+                let scale = 1.0 / (1i64 << (spec.bits_per_sample - 1)) as f32;
+                reader
+                    .samples::<i32>()
+                    .map(|sample| sample.map(|sample| sample as f32 * scale))
+                    .collect()
+            }
+        }
+        .map_err(error)?;
+        Ok(Sound::from_samples(&samples, spec.channels, spec.sample_rate))
+    }
+
+    /// Interleaved samples with `channels` channels at `sample_rate`. Mono
+    /// is spread to both sides; channels past the second are dropped.
+    pub fn from_samples(samples: &[f32], channels: u16, sample_rate: u32) -> Sound {
+        let channels = channels.max(1) as usize;
+        let frames: Vec<[f32; 2]> = samples
+            .chunks_exact(channels)
+            .map(|frame| [frame[0], frame[if channels > 1 { 1 } else { 0 }]])
+            .collect();
+
+        // Linear resampling to SAMPLE_RATE
+        let length = (frames.len() as u64 * SAMPLE_RATE as u64 / sample_rate.max(1) as u64) as usize;
+        let step = sample_rate as f32 / SAMPLE_RATE as f32;
+        let mut resampled = Vec::with_capacity(length * CHANNELS);
+        for i in 0..length {
+            let position = i as f32 * step;
+            let index = position as usize;
+            let next = (index + 1).min(frames.len() - 1);
+            let f = position - index as f32;
+            for (&a, &b) in frames[index].iter().zip(&frames[next]) {
+                resampled.push(a + (b - a) * f);
+            }
+        }
+        Sound { samples: resampled }
+    }
+
+    pub fn get_frame_count(&self) -> usize {
+        self.samples.len() / CHANNELS
+    }
+
+    pub fn get_duration(&self) -> f32 {
+        self.get_frame_count() as f32 / SAMPLE_RATE as f32
+    }
+}
+
+/// Handle to one playing sound, from `Audio::play`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VoiceId(u64);
+
+struct Voice {
+    id: VoiceId,
+    sound: Arc<Sound>,
+    channel: Channel,
+    looping: bool,
+    // Next frame to play
+    position: usize,
+}
+
+/// Adds up the playing sounds. Shared with SDL's audio thread.
+pub struct Mixer {
+    voices: Vec<Voice>,
+    effects_volume: f32,
+    music_volume: f32,
+    muted: bool,
+    next_id: u64,
+}
+
+impl Mixer {
+    pub fn new() -> Self {
+        Mixer {
+            voices: Vec::new(),
+            effects_volume: 1.0,
+            music_volume: 1.0,
+            muted: false,
+            next_id: 0,
+        }
+    }
+
+    pub fn play(&mut self, sound: Arc<Sound>, channel: Channel, looping: bool) -> VoiceId {
+        let id = VoiceId(self.next_id);
+        self.next_id += 1;
+        self.voices.push(Voice {
+            id,
+            sound,
+            channel,
+            looping,
+            position: 0,
+        });
+        id
+    }
+
+    pub fn stop(&mut self, id: VoiceId) {
+        self.voices.retain(|voice| voice.id != id);
+    }
+
+    pub fn stop_channel(&mut self, channel: Channel) {
+        self.voices.retain(|voice| voice.channel != channel);
+    }
+
+    pub fn is_playing(&self, id: VoiceId) -> bool {
+        self.voices.iter().any(|voice| voice.id == id)
+    }
+
+    pub fn get_voice_count(&self) -> usize {
+        self.voices.len()
+    }
+
+    pub fn get_volume(&self, channel: Channel) -> f32 {
+        match channel {
+            Channel::Effects => self.effects_volume,
+            Channel::Music => self.music_volume,
+        }
+    }
+
+    pub fn set_volume(&mut self, channel: Channel, volume: f32) {
+        let volume = volume.clamp(0.0, 1.0);
+        match channel {
+            Channel::Effects => self.effects_volume = volume,
+            Channel::Music => self.music_volume = volume,
+        }
+    }
+
+    pub fn is_muted(&self) -> bool {
+        self.muted
+    }
+
+    // Muted sounds keep playing silently, so unmuting picks up where they are
+    pub fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
+    }
+
+    /// Mix the next `out.len() / 2` frames into `out` (interleaved stereo),
+    /// on top of what's already there.
+    pub fn mix(&mut self, out: &mut [f32]) {
+        let frames = out.len() / CHANNELS;
+        let effects_volume = self.get_volume(Channel::Effects);
+        let music_volume = self.get_volume(Channel::Music);
+        let gain = if self.muted { 0.0 } else { 1.0 };
+
+        self.voices.retain_mut(|voice| {
+            let samples = &voice.sound.samples;
+            let sound_frames = samples.len() / CHANNELS;
+            if sound_frames == 0 {
+                return false;
+            }
+            let volume = gain * match voice.channel {
+                Channel::Effects => effects_volume,
+                Channel::Music => music_volume,
+            };
+
+            for frame in 0..frames {
+                if voice.position >= sound_frames {
+                    if !voice.looping {
+                        return false;
+                    }
+                    voice.position = 0;
+                }
+                let start = voice.position * CHANNELS;
+                for side in 0..CHANNELS {
+                    out[frame * CHANNELS + side] += samples[start + side] * volume;
+                }
+                voice.position += 1;
+            }
+            voice.looping || voice.position < sound_frames
+        });
+
+        for sample in out.iter_mut() {
+            *sample = sample.clamp(-1.0, 1.0);
+        }
+    }
+}
+
+impl Default for Mixer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Runs on SDL's audio thread
+struct MixerCallback {
+    mixer: Arc<Mutex<Mixer>>,
+}
+
+impl AudioCallback for MixerCallback {
+    type Channel = f32;
+
+    fn callback(&mut self, out: &mut [f32]) {
+        out.fill(0.0);
+        if let Ok(mut mixer) = self.mixer.lock() {
+            mixer.mix(out);
+        }
+    }
+}
+
+enum Output {
+    // Only held to keep the device playing; it pulls from the mixer itself
+    Sdl { _device: AudioDevice<MixerCallback> },
+    Null,
+    WaveFile(hound::WavWriter<BufWriter<File>>),
+}
+
+/// The game's sound: plays effects and music through the mixer and sends
+/// the result to an `AudioOutput`.
+pub struct Audio {
+    mixer: Arc<Mutex<Mixer>>,
+    output: Output,
+    music: Option<VoiceId>,
+    // Game time not yet mixed, for the outputs that follow the game clock
+    pending_frames: f64,
+}
+
+impl Audio {
+    /// Falls back to `AudioOutput::Null` (with a message) if the output
+    /// can't be opened, or if it needs SDL and `sdl` is None.
+    pub fn new(output: &AudioOutput, sdl: Option<&Sdl>) -> Audio {
+        let mixer = Arc::new(Mutex::new(Mixer::new()));
+        let output = match output {
+            AudioOutput::Sdl => match sdl.map(|sdl| Audio::open_device(sdl, &mixer)) {
+                Some(Ok(device)) => Output::Sdl { _device: device },
+                Some(Err(err)) => {
+                    eprintln!("Audio unavailable: {}", err);
+                    Output::Null
+                }
+                None => Output::Null,
+            },
+            AudioOutput::Null => Output::Null,
+            AudioOutput::WaveFile(path) => {
+                let spec = hound::WavSpec {
+                    channels: CHANNELS as u16,
+                    sample_rate: SAMPLE_RATE,
+                    bits_per_sample: 32,
+                    sample_format: hound::SampleFormat::Float,
+                };
+                match hound::WavWriter::create(path, spec) {
+                    Ok(writer) => Output::WaveFile(writer),
+                    Err(err) => {
+                        eprintln!("Failed to create {}: {}", path.display(), err);
+                        Output::Null
+                    }
+                }
+            }
+        };
+        Audio {
+            mixer,
+            output,
+            music: None,
+            pending_frames: 0.0,
+        }
+    }
+
+    fn open_device(sdl: &Sdl, mixer: &Arc<Mutex<Mixer>>) -> Result<AudioDevice<MixerCallback>, String> {
+        let desired = AudioSpecDesired {
+            freq: Some(SAMPLE_RATE as i32),
+            channels: Some(CHANNELS as u8),
+            samples: Some(1024),
+        };
+        let mixer = Arc::clone(mixer);
+        let device = sdl.audio()?.open_playback(None, &desired, |_spec| MixerCallback { mixer })?;
+        device.resume();
+        Ok(device)
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self.output, Output::Null)
+    }
+
+    /// Run `f` on the mixer. Keep it short; the audio thread waits on it.
+    pub fn with_mixer<R>(&self, f: impl FnOnce(&mut Mixer) -> R) -> R {
+        let mut mixer = self.mixer.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        f(&mut mixer)
+    }
+
+    pub fn play(&mut self, sound: &Arc<Sound>, channel: Channel) -> VoiceId {
+        self.with_mixer(|mixer| mixer.play(Arc::clone(sound), channel, false))
+    }
+
+    /// Loop `sound` on the music channel, replacing the current music.
+    pub fn play_music(&mut self, sound: &Arc<Sound>) -> VoiceId {
+        self.stop_music();
+        let id = self.with_mixer(|mixer| mixer.play(Arc::clone(sound), Channel::Music, true));
+        self.music = Some(id);
+        id
+    }
+
+    pub fn stop_music(&mut self) {
+        if let Some(music) = self.music.take() {
+            self.stop(music);
+        }
+    }
+
+    pub fn stop(&mut self, id: VoiceId) {
+        self.with_mixer(|mixer| mixer.stop(id));
+    }
+
+    pub fn is_playing(&self, id: VoiceId) -> bool {
+        self.with_mixer(|mixer| mixer.is_playing(id))
+    }
+
+    pub fn set_volume(&mut self, channel: Channel, volume: f32) {
+        self.with_mixer(|mixer| mixer.set_volume(channel, volume));
+    }
+
+    pub fn toggle_mute(&mut self) {
+        self.with_mixer(|mixer| mixer.set_muted(!mixer.is_muted()));
+    }
+
+    /// Advance the null and wave file outputs by `delta_time` of game time.
+    /// SDL pulls from the mixer on its own thread, so it ignores this.
+    pub fn update(&mut self, delta_time: f32) {
+        if matches!(self.output, Output::Sdl { .. }) {
+            return;
+        }
+        self.pending_frames += delta_time.max(0.0) as f64 * SAMPLE_RATE as f64;
+        let frames = self.pending_frames as usize;
+        self.pending_frames -= frames as f64;
+
+        let mut buffer = vec![0.0; frames * CHANNELS];
+        self.with_mixer(|mixer| mixer.mix(&mut buffer));
+        if let Output::WaveFile(writer) = &mut self.output {
+            for sample in buffer {
+                if let Err(err) = writer.write_sample(sample) {
+                    eprintln!("Failed to write audio: {}", err);
+                    self.output = Output::Null;
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Constant level, so mixed values are easy to predict
+    fn tone(level: f32, frames: usize) -> Arc<Sound> {
+        Arc::new(Sound::from_samples(&vec![level; frames * CHANNELS], CHANNELS as u16, SAMPLE_RATE))
+    }
+
+    #[test]
+    fn mono_is_spread_and_resampled() {
+        let sound = Sound::from_samples(&[0.0, 1.0, 0.0, 1.0], 1, SAMPLE_RATE / 2);
+        assert_eq!(sound.get_frame_count(), 8);
+        assert_eq!(&sound.samples[..6], &[0.0, 0.0, 0.5, 0.5, 1.0, 1.0]);
+        assert!((Sound::from_samples(&[0.0; 22050], 1, 22050).get_duration() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn voices_add_up_with_channel_volume() {
+        let mut mixer = Mixer::new();
+        mixer.play(tone(0.25, 10), Channel::Effects, false);
+        mixer.play(tone(0.5, 10), Channel::Music, false);
+        mixer.set_volume(Channel::Music, 0.5);
+
+        let mut out = [0.0; 4];
+        mixer.mix(&mut out);
+        assert_eq!(out, [0.5; 4]);
+
+        // Loud enough to clip
+        mixer.set_volume(Channel::Effects, 1.0);
+        mixer.play(tone(1.0, 10), Channel::Effects, false);
+        let mut out = [0.0; 2];
+        mixer.mix(&mut out);
+        assert_eq!(out, [1.0; 2]);
+    }
+
+    #[test]
+    fn one_shots_finish_and_loops_wrap() {
+        let mut mixer = Mixer::new();
+        let one_shot = mixer.play(tone(0.1, 3), Channel::Effects, false);
+        let music = mixer.play(tone(0.2, 3), Channel::Music, true);
+
+        let mut out = [0.0; 5 * CHANNELS];
+        mixer.mix(&mut out);
+        assert!(!mixer.is_playing(one_shot));
+        assert!(mixer.is_playing(music));
+        assert!((out[4 * CHANNELS] - 0.2).abs() < 1e-6);
+
+        mixer.stop_channel(Channel::Music);
+        assert_eq!(mixer.get_voice_count(), 0);
+    }
+
+    #[test]
+    fn mute_silences_but_keeps_playing() {
+        let mut mixer = Mixer::new();
+        let id = mixer.play(tone(0.5, 4), Channel::Effects, false);
+        mixer.set_muted(true);
+        let mut out = [0.0; 2 * CHANNELS];
+        mixer.mix(&mut out);
+        assert_eq!(out, [0.0; 4]);
+
+        mixer.set_muted(false);
+        mixer.mix(&mut out);
+        assert_eq!(out, [0.5; 4]);
+        assert!(!mixer.is_playing(id));
+    }
+
+    #[test]
+    fn wave_file_output_follows_game_time() {
+        let path = std::env::temp_dir().join(format!("asteroid-audio-{}.wav", std::process::id()));
+        {
+            let mut audio = Audio::new(&AudioOutput::WaveFile(path.clone()), None);
+            let music = audio.play_music(&tone(0.25, 100));
+            audio.set_volume(Channel::Music, 0.5);
+            audio.update(0.125);
+            audio.update(0.125);
+            assert!(audio.is_playing(music));
+            audio.stop_music();
+            assert!(!audio.is_playing(music));
+        }
+
+        let sound = Sound::load(&path);
+        let _ = std::fs::remove_file(&path);
+        let sound = sound.unwrap();
+        assert_eq!(sound.get_frame_count(), 11025);
+        assert!(sound.samples.iter().all(|&sample| (sample - 0.125).abs() < 1e-6));
+    }
+
+    #[test]
+    fn missing_file_is_an_error() {
+        assert!(Sound::load("Assets/DoesNotExist.wav").is_err());
+        assert!(Audio::new(&AudioOutput::Sdl, None).is_null());
+    }
+}
@@ -1,60 +1,91 @@
-use std::cell::RefCell;
-use std::rc::Rc;
-use crate::actor::Actor;
+use crate::actor::ActorId;
+use crate::collision::Shape;
+use crate::collision_world::layers;
+use crate::game::Game;
 use crate::math::Vector2;
-use crate::component::{Component, BaseComponent};
+use crate::component::{Component, BaseComponent, ComponentId};
 
 #[derive(Clone)]
 pub struct CircleComponent {
     base: BaseComponent,
     radius: f32,
+    layer: u32,
+    mask: u32,
 }
 
 impl CircleComponent {
-    pub fn new(owner: Rc<RefCell<Actor>>) -> Self {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(game: &mut Game, owner: ActorId) -> ComponentId {
         let base = BaseComponent::new(owner, 100);
-        CircleComponent { base, radius: 0.0 }
+        let id = game.add_component(Box::new(CircleComponent {
+            base,
+            radius: 0.0,
+            layer: layers::DEFAULT,
+            mask: layers::ALL,
+        }));
+        game.get_collision_world_mut().add_collider(id);
+        id
+    }
+
+    pub fn get_layer(&self) -> u32 {
+        self.layer
+    }
+
+    pub fn get_mask(&self) -> u32 {
+        self.mask
+    }
+
+    // Layers this collider is on, and layers it collides with
+    pub fn set_layer(&mut self, layer: u32) {
+        self.layer = layer;
+    }
+
+    pub fn set_mask(&mut self, mask: u32) {
+        self.mask = mask;
     }
 
     pub fn set_radius(&mut self, radius: f32) {
         self.radius = radius;
     }
 
-    pub fn get_radius(&self) -> f32 {
-        self.base.owner.borrow().get_scale() * self.radius
+    pub fn get_radius(&self, game: &Game) -> f32 {
+        game.get_actor(self.base.owner)
+            .map_or(0.0, |owner| owner.get_scale() * self.radius)
     }
 
-    pub fn get_center(&self) -> Vector2 {
-        self.base.owner.borrow().get_position()
+    pub fn get_center(&self, game: &Game) -> Vector2 {
+        game.get_actor(self.base.owner)
+            .map_or(Vector2::zero(), |owner| owner.get_position())
     }
-}
 
-impl Component for CircleComponent {
-    fn new(owner: Rc<RefCell<Actor>>, update_order: i32) -> Self {
-        CircleComponent::new(owner)
+    pub fn get_shape(&self, game: &Game) -> Shape {
+        Shape::Circle {
+            center: self.get_center(game),
+            radius: self.get_radius(game),
+        }
     }
+}
 
-    fn update(&mut self, _delta_time: f32) {}
+impl Component for CircleComponent {
+    fn update(&mut self, _game: &mut Game, _delta_time: f32) {}
 
     fn get_update_order(&self) -> i32 {
         self.base.get_update_order()
     }
-}
 
-impl Drop for CircleComponent {
-    fn drop(&mut self) {
-        self.base.owner.borrow_mut().remove_component(Rc::new(RefCell::new(self.clone())));
+    fn get_owner(&self) -> ActorId {
+        self.base.get_owner()
     }
 }
 
 // 円の衝突判定
-pub fn intersect(a: &CircleComponent, b: &CircleComponent) -> bool {
+pub fn intersect(game: &Game, a: &CircleComponent, b: &CircleComponent) -> bool {
     // Calculate distance squared
-    let diff = a.get_center() - b.get_center();
+    let diff = a.get_center(game) - b.get_center(game);
     let dist_sq = diff.length_sq();
 
     // Calculate sum of radii squared
-    let radii_sum = a.get_radius() + b.get_radius();
+    let radii_sum = a.get_radius(game) + b.get_radius(game);
     let radii_sq = radii_sum * radii_sum;
 
     dist_sq <= radii_sq
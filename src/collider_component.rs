@@ -0,0 +1,107 @@
+use crate::actor::ActorId;
+use crate::collision::Shape;
+use crate::collision_world::layers;
+use crate::component::{Component, BaseComponent, ComponentId};
+use crate::game::Game;
+use crate::math::Vector2;
+
+/// Collider outline in the owner's local space. Everything scales with the
+/// owner and follows its position; only `Box` ignores its rotation.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ColliderShape {
+    Box { half_extents: Vector2 },
+    OrientedBox { half_extents: Vector2 },
+    /// Convex outline, e.g. an irregular asteroid.
+    Polygon { vertices: Vec<Vector2> },
+    /// Line from `start` to `end`, e.g. a laser beam.
+    Segment { start: Vector2, end: Vector2 },
+}
+
+pub struct ColliderComponent {
+    base: BaseComponent,
+    shape: ColliderShape,
+    layer: u32,
+    mask: u32,
+}
+
+impl ColliderComponent {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(game: &mut Game, owner: ActorId, shape: ColliderShape) -> ComponentId {
+        let base = BaseComponent::new(owner, 100);
+        let id = game.add_component(Box::new(ColliderComponent {
+            base,
+            shape,
+            layer: layers::DEFAULT,
+            mask: layers::ALL,
+        }));
+        game.get_collision_world_mut().add_collider(id);
+        id
+    }
+
+    pub fn get_shape(&self) -> &ColliderShape {
+        &self.shape
+    }
+
+    pub fn set_shape(&mut self, shape: ColliderShape) {
+        self.shape = shape;
+    }
+
+    pub fn get_layer(&self) -> u32 {
+        self.layer
+    }
+
+    pub fn get_mask(&self) -> u32 {
+        self.mask
+    }
+
+    pub fn set_layer(&mut self, layer: u32) {
+        self.layer = layer;
+    }
+
+    pub fn set_mask(&mut self, mask: u32) {
+        self.mask = mask;
+    }
+
+    // The outline placed by the owner's position, rotation and scale
+    pub fn get_world_shape(&self, game: &Game) -> Option<Shape> {
+        let owner = game.get_actor(self.base.owner)?;
+        let position = owner.get_position();
+        let rotation = owner.get_rotation();
+        let scale = owner.get_scale();
+        let to_world = |v: Vector2| position + Vector2::rotate(v * scale, rotation);
+
+        Some(match &self.shape {
+            ColliderShape::Box { half_extents } => {
+                let half_extents = *half_extents * scale;
+                Shape::Aabb {
+                    min: position - half_extents,
+                    max: position + half_extents,
+                }
+            }
+            ColliderShape::OrientedBox { half_extents } => Shape::Obb {
+                center: position,
+                half_extents: *half_extents * scale,
+                rotation,
+            },
+            ColliderShape::Polygon { vertices } => Shape::Polygon {
+                vertices: vertices.iter().map(|&v| to_world(v)).collect(),
+            },
+            ColliderShape::Segment { start, end } => Shape::Segment {
+                start: to_world(*start),
+                end: to_world(*end),
+            },
+        })
+    }
+}
+
+impl Component for ColliderComponent {
+    fn update(&mut self, _game: &mut Game, _delta_time: f32) {}
+
+    fn get_update_order(&self) -> i32 {
+        self.base.get_update_order()
+    }
+
+    fn get_owner(&self) -> ActorId {
+        self.base.get_owner()
+    }
+}
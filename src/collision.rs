@@ -0,0 +1,493 @@
+use crate::math::Vector2;
+
+/// Collider geometry in world space.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Shape {
+    Circle { center: Vector2, radius: f32 },
+    /// Axis-aligned box.
+    Aabb { min: Vector2, max: Vector2 },
+    /// Box rotated by `rotation` radians around its center.
+    Obb { center: Vector2, half_extents: Vector2, rotation: f32 },
+    /// Convex polygon, vertices in order (either winding).
+    Polygon { vertices: Vec<Vector2> },
+    Segment { start: Vector2, end: Vector2 },
+}
+
+/// How two overlapping shapes touch. `normal` is a unit vector pointing
+/// from the first shape towards the second; moving the second one by
+/// `normal * penetration` separates them.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Contact {
+    pub normal: Vector2,
+    pub penetration: f32,
+}
+
+impl Contact {
+    pub fn flipped(self) -> Contact {
+        Contact {
+            normal: self.normal * -1.0,
+            penetration: self.penetration,
+        }
+    }
+}
+
+/// Where a ray first enters a shape.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RayHit {
+    pub distance: f32,
+    pub point: Vector2,
+    /// Surface normal at `point`, facing back against the ray.
+    pub normal: Vector2,
+}
+
+impl Shape {
+    pub fn get_center(&self) -> Vector2 {
+        match self {
+            Shape::Circle { center, .. } | Shape::Obb { center, .. } => *center,
+            Shape::Aabb { min, max } => (*min + *max) * 0.5,
+            Shape::Segment { start, end } => (*start + *end) * 0.5,
+            Shape::Polygon { vertices } => centroid(vertices),
+        }
+    }
+
+    /// Axis-aligned bounds as (min, max).
+    pub fn get_bounds(&self) -> (Vector2, Vector2) {
+        match self {
+            Shape::Circle { center, radius } => (
+                *center - Vector2::new(*radius, *radius),
+                *center + Vector2::new(*radius, *radius),
+            ),
+            Shape::Aabb { min, max } => (*min, *max),
+            _ => {
+                let vertices = self.get_vertices();
+                let mut min = Vector2::new(f32::MAX, f32::MAX);
+                let mut max = Vector2::new(f32::MIN, f32::MIN);
+                for v in vertices {
+                    min = Vector2::new(min.x.min(v.x), min.y.min(v.y));
+                    max = Vector2::new(max.x.max(v.x), max.y.max(v.y));
+                }
+                (min, max)
+            }
+        }
+    }
+
+    pub fn translated(&self, offset: Vector2) -> Shape {
+        match self {
+            Shape::Circle { center, radius } => Shape::Circle {
+                center: *center + offset,
+                radius: *radius,
+            },
+            Shape::Aabb { min, max } => Shape::Aabb {
+                min: *min + offset,
+                max: *max + offset,
+            },
+            Shape::Obb { center, half_extents, rotation } => Shape::Obb {
+                center: *center + offset,
+                half_extents: *half_extents,
+                rotation: *rotation,
+            },
+            Shape::Polygon { vertices } => Shape::Polygon {
+                vertices: vertices.iter().map(|&v| v + offset).collect(),
+            },
+            Shape::Segment { start, end } => Shape::Segment {
+                start: *start + offset,
+                end: *end + offset,
+            },
+        }
+    }
+
+    // Corners of everything but circles
+    fn get_vertices(&self) -> Vec<Vector2> {
+        match self {
+            Shape::Circle { center, .. } => vec![*center],
+            Shape::Aabb { min, max } => vec![
+                *min,
+                Vector2::new(max.x, min.y),
+                *max,
+                Vector2::new(min.x, max.y),
+            ],
+            Shape::Obb { center, half_extents, rotation } => {
+                let (x, y) = (half_extents.x, half_extents.y);
+                [(-x, -y), (x, -y), (x, y), (-x, y)]
+                    .iter()
+                    .map(|&(cx, cy)| *center + Vector2::rotate(Vector2::new(cx, cy), *rotation))
+                    .collect()
+            }
+            Shape::Polygon { vertices } => vertices.clone(),
+            Shape::Segment { start, end } => vec![*start, *end],
+        }
+    }
+
+    // Separating axis candidates contributed by this shape
+    fn get_axes(&self) -> Vec<Vector2> {
+        let vertices = self.get_vertices();
+        let mut axes: Vec<Vector2> = (0..vertices.len())
+            .map(|i| (vertices[(i + 1) % vertices.len()] - vertices[i]).perpendicular())
+            .collect();
+        // A segment also separates along its own direction (collinear case)
+        if let Shape::Segment { start, end } = self {
+            axes.push(*end - *start);
+        }
+        axes.into_iter().filter_map(normalized).collect()
+    }
+
+    // Interval covered on `axis`
+    fn project(&self, axis: Vector2) -> (f32, f32) {
+        if let Shape::Circle { center, radius } = self {
+            let c = Vector2::dot(*center, axis);
+            return (c - radius, c + radius);
+        }
+        self.get_vertices()
+            .iter()
+            .map(|&v| Vector2::dot(v, axis))
+            .fold((f32::MAX, f32::MIN), |(min, max), d| (min.min(d), max.max(d)))
+    }
+}
+
+/// Test two shapes for overlap. Touching counts as overlapping, like
+/// `circle_component::intersect`.
+pub fn collide(a: &Shape, b: &Shape) -> Option<Contact> {
+    match (a, b) {
+        (Shape::Circle { center: ca, radius: ra }, Shape::Circle { center: cb, radius: rb }) => {
+            let diff = *cb - *ca;
+            let radii = ra + rb;
+            let dist_sq = diff.length_sq();
+            if dist_sq > radii * radii {
+                return None;
+            }
+            let dist = dist_sq.sqrt();
+            let normal = normalized(diff).unwrap_or(Vector2::new(1.0, 0.0));
+            Some(Contact {
+                normal,
+                penetration: radii - dist,
+            })
+        }
+        (Shape::Circle { center, .. }, _) => {
+            let mut axes = b.get_axes();
+            // The axis towards the closest point catches corner cases
+            if let Some(axis) = normalized(closest_point_on_outline(b, *center) - *center) {
+                axes.push(axis);
+            }
+            separating_axis(a, b, &axes)
+        }
+        (_, Shape::Circle { .. }) => collide(b, a).map(Contact::flipped),
+        _ => {
+            let mut axes = a.get_axes();
+            axes.extend(b.get_axes());
+            separating_axis(a, b, &axes)
+        }
+    }
+}
+
+/// Cast a ray from `origin` along unit `direction` up to `max_distance`.
+/// A ray starting inside a solid shape hits at distance 0.
+pub fn ray_cast(shape: &Shape, origin: Vector2, direction: Vector2, max_distance: f32) -> Option<RayHit> {
+    let hit = match shape {
+        Shape::Circle { center, radius } => ray_circle(*center, *radius, origin, direction),
+        Shape::Segment { start, end } => ray_segment(*start, *end, origin, direction),
+        _ => ray_polygon(&shape.get_vertices(), origin, direction),
+    }?;
+    (hit.distance <= max_distance).then_some(hit)
+}
+
+fn separating_axis(a: &Shape, b: &Shape, axes: &[Vector2]) -> Option<Contact> {
+    let mut best: Option<Contact> = None;
+    for &axis in axes {
+        let (min_a, max_a) = a.project(axis);
+        let (min_b, max_b) = b.project(axis);
+        let overlap = (max_a - min_b).min(max_b - min_a);
+        if overlap < 0.0 {
+            return None;
+        }
+        if best.is_none_or(|contact| overlap < contact.penetration) {
+            best = Some(Contact {
+                normal: axis,
+                penetration: overlap,
+            });
+        }
+    }
+
+    // Point the normal from a towards b
+    best.map(|contact| {
+        if Vector2::dot(b.get_center() - a.get_center(), contact.normal) < 0.0 {
+            contact.flipped()
+        } else {
+            contact
+        }
+    })
+}
+
+fn ray_circle(center: Vector2, radius: f32, origin: Vector2, direction: Vector2) -> Option<RayHit> {
+    let to_origin = origin - center;
+    let c = to_origin.length_sq() - radius * radius;
+    if c <= 0.0 {
+        return Some(RayHit {
+            distance: 0.0,
+            point: origin,
+            normal: direction * -1.0,
+        });
+    }
+
+    let b = Vector2::dot(to_origin, direction);
+    let discriminant = b * b - c;
+    if b > 0.0 || discriminant < 0.0 {
+        return None;
+    }
+    let distance = -b - discriminant.sqrt();
+    let point = origin + direction * distance;
+    Some(RayHit {
+        distance,
+        point,
+        normal: normalized(point - center).unwrap_or(direction * -1.0),
+    })
+}
+
+fn ray_segment(start: Vector2, end: Vector2, origin: Vector2, direction: Vector2) -> Option<RayHit> {
+    let edge = end - start;
+    let denom = Vector2::cross(direction, edge);
+    if denom.abs() <= f32::EPSILON {
+        return None;
+    }
+    let to_start = start - origin;
+    let distance = Vector2::cross(to_start, edge) / denom;
+    let along = Vector2::cross(to_start, direction) / denom;
+    if distance < 0.0 || !(0.0..=1.0).contains(&along) {
+        return None;
+    }
+
+    let mut normal = normalized(edge.perpendicular())?;
+    if Vector2::dot(normal, direction) > 0.0 {
+        normal *= -1.0;
+    }
+    Some(RayHit {
+        distance,
+        point: origin + direction * distance,
+        normal,
+    })
+}
+
+// Cyrus-Beck clipping of the ray against the polygon's edges
+fn ray_polygon(vertices: &[Vector2], origin: Vector2, direction: Vector2) -> Option<RayHit> {
+    let center = centroid(vertices);
+    let mut t_enter = 0.0;
+    let mut t_exit = f32::MAX;
+    let mut enter_normal = None;
+
+    for i in 0..vertices.len() {
+        let v0 = vertices[i];
+        let v1 = vertices[(i + 1) % vertices.len()];
+        let Some(mut normal) = normalized((v1 - v0).perpendicular()) else {
+            continue;
+        };
+        // Outward regardless of winding
+        if Vector2::dot(normal, v0 - center) < 0.0 {
+            normal *= -1.0;
+        }
+
+        let distance = Vector2::dot(normal, v0 - origin);
+        let denom = Vector2::dot(normal, direction);
+        if denom.abs() <= f32::EPSILON {
+            if distance < 0.0 {
+                return None;
+            }
+            continue;
+        }
+
+        let t = distance / denom;
+        if denom < 0.0 {
+            if t > t_enter {
+                t_enter = t;
+                enter_normal = Some(normal);
+            }
+        } else {
+            t_exit = t_exit.min(t);
+        }
+        if t_enter > t_exit {
+            return None;
+        }
+    }
+
+    Some(RayHit {
+        distance: t_enter,
+        point: origin + direction * t_enter,
+        normal: enter_normal.unwrap_or(direction * -1.0),
+    })
+}
+
+fn closest_point_on_outline(shape: &Shape, point: Vector2) -> Vector2 {
+    let vertices = shape.get_vertices();
+    let edges = if vertices.len() == 2 { 1 } else { vertices.len() };
+    (0..edges)
+        .map(|i| closest_point_on_segment(vertices[i], vertices[(i + 1) % vertices.len()], point))
+        .min_by(|p, q| (*p - point).length_sq().total_cmp(&(*q - point).length_sq()))
+        .unwrap_or(point)
+}
+
+pub fn closest_point_on_segment(start: Vector2, end: Vector2, point: Vector2) -> Vector2 {
+    let edge = end - start;
+    let length_sq = edge.length_sq();
+    if length_sq <= f32::EPSILON {
+        return start;
+    }
+    let t = (Vector2::dot(point - start, edge) / length_sq).clamp(0.0, 1.0);
+    start + edge * t
+}
+
+fn centroid(vertices: &[Vector2]) -> Vector2 {
+    let sum = vertices.iter().fold(Vector2::zero(), |sum, &v| sum + v);
+    sum * (1.0 / vertices.len().max(1) as f32)
+}
+
+fn normalized(v: Vector2) -> Option<Vector2> {
+    let length = v.length();
+    (length > f32::EPSILON).then(|| v * (1.0 / length))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(a: Vector2, b: Vector2) {
+        assert!((a - b).length() < 1e-4, "{:?} != {:?}", a, b);
+    }
+
+    fn circle(x: f32, y: f32, radius: f32) -> Shape {
+        Shape::Circle { center: Vector2::new(x, y), radius }
+    }
+
+    fn aabb(x: f32, y: f32, half: f32) -> Shape {
+        Shape::Aabb {
+            min: Vector2::new(x - half, y - half),
+            max: Vector2::new(x + half, y + half),
+        }
+    }
+
+    fn obb(x: f32, y: f32, half: f32, rotation: f32) -> Shape {
+        Shape::Obb {
+            center: Vector2::new(x, y),
+            half_extents: Vector2::new(half, half),
+            rotation,
+        }
+    }
+
+    fn segment(x0: f32, y0: f32, x1: f32, y1: f32) -> Shape {
+        Shape::Segment { start: Vector2::new(x0, y0), end: Vector2::new(x1, y1) }
+    }
+
+    fn triangle(x: f32, y: f32) -> Shape {
+        Shape::Polygon {
+            vertices: vec![Vector2::new(x, y), Vector2::new(x + 10.0, y), Vector2::new(x, y + 10.0)],
+        }
+    }
+
+    #[test]
+    fn circles_report_normal_and_penetration() {
+        let contact = collide(&circle(0.0, 0.0, 10.0), &circle(15.0, 0.0, 10.0)).unwrap();
+        assert_near(contact.normal, Vector2::new(1.0, 0.0));
+        assert!((contact.penetration - 5.0).abs() < 1e-4);
+        assert!(collide(&circle(0.0, 0.0, 10.0), &circle(25.0, 0.0, 10.0)).is_none());
+    }
+
+    #[test]
+    fn circle_against_box_corner_uses_closest_point() {
+        // Diagonal off the corner: the box axes overlap but the circle misses
+        assert!(collide(&circle(13.0, 13.0, 4.0), &aabb(0.0, 0.0, 10.0)).is_none());
+
+        let contact = collide(&aabb(0.0, 0.0, 10.0), &circle(0.0, 18.0, 10.0)).unwrap();
+        assert_near(contact.normal, Vector2::new(0.0, 1.0));
+        assert!((contact.penetration - 2.0).abs() < 1e-4);
+
+        let flipped = collide(&circle(0.0, 18.0, 10.0), &aabb(0.0, 0.0, 10.0)).unwrap();
+        assert_near(flipped.normal, Vector2::new(0.0, -1.0));
+    }
+
+    #[test]
+    fn boxes_pick_the_shallowest_axis() {
+        let contact = collide(&aabb(0.0, 0.0, 10.0), &aabb(-18.0, 3.0, 10.0)).unwrap();
+        assert_near(contact.normal, Vector2::new(-1.0, 0.0));
+        assert!((contact.penetration - 2.0).abs() < 1e-4);
+        assert!(collide(&aabb(0.0, 0.0, 10.0), &aabb(21.0, 0.0, 10.0)).is_none());
+    }
+
+    #[test]
+    fn oriented_box_is_tested_on_its_own_axes() {
+        // A diamond whose bounding box would overlap, but the shape doesn't
+        let diamond = obb(20.0, 20.0, 10.0, std::f32::consts::FRAC_PI_4);
+        assert!(collide(&aabb(0.0, 0.0, 10.0), &diamond).is_none());
+
+        let closer = diamond.translated(Vector2::new(-5.0, -5.0));
+        assert!(collide(&aabb(0.0, 0.0, 10.0), &closer).is_some());
+    }
+
+    #[test]
+    fn oriented_boxes_against_everything() {
+        // Corners on the axes, 10√2 from the center
+        let diamond = obb(0.0, 0.0, 10.0, std::f32::consts::FRAC_PI_4);
+
+        assert!(collide(&diamond, &obb(30.0, 0.0, 10.0, std::f32::consts::FRAC_PI_4)).is_none());
+        let contact = collide(&diamond, &obb(26.0, 0.0, 10.0, std::f32::consts::FRAC_PI_4)).unwrap();
+        assert!(contact.normal.x > 0.0);
+        assert!((contact.penetration - (20.0 - 26.0 / 2.0f32.sqrt())).abs() < 1e-3);
+
+        // Inside the diamond's bounding box, outside the diamond
+        assert!(collide(&circle(12.0, 12.0, 3.0), &diamond).is_none());
+        assert!(collide(&circle(16.0, 0.0, 3.0), &diamond).is_some());
+
+        assert!(collide(&segment(12.0, -5.0, 12.0, 5.0), &diamond).is_some());
+        assert!(collide(&segment(15.0, -5.0, 15.0, 5.0), &diamond).is_none());
+        assert!(collide(&triangle(8.0, 8.0), &diamond).is_none());
+        assert!(collide(&triangle(4.0, 4.0), &diamond).is_some());
+
+        let hit = ray_cast(&diamond, Vector2::new(-30.0, 0.0), Vector2::new(1.0, 0.0), 100.0).unwrap();
+        assert!((hit.distance - (30.0 - 200.0f32.sqrt())).abs() < 1e-3);
+    }
+
+    #[test]
+    fn convex_polygons_and_boxes() {
+        assert!(collide(&triangle(0.0, 0.0), &triangle(4.0, 4.0)).is_some());
+        // Bounding boxes touch but the hypotenuse keeps them apart
+        assert!(collide(&triangle(0.0, 0.0), &aabb(9.0, 9.0, 2.0)).is_none());
+        assert!(collide(&triangle(0.0, 0.0), &aabb(6.0, 1.0, 2.0)).is_some());
+    }
+
+    #[test]
+    fn segments_against_everything() {
+        let diagonal = segment(-10.0, -10.0, 10.0, 10.0);
+        assert!(collide(&diagonal, &segment(-10.0, 10.0, 10.0, -10.0)).is_some());
+        // Collinear but disjoint
+        assert!(collide(&segment(0.0, 0.0, 1.0, 0.0), &segment(2.0, 0.0, 3.0, 0.0)).is_none());
+
+        assert!(collide(&diagonal, &circle(10.0, -10.0, 5.0)).is_none());
+        let contact = collide(&circle(0.0, 5.0, 5.0), &segment(-10.0, 0.0, 10.0, 0.0)).unwrap();
+        assert_near(contact.normal, Vector2::new(0.0, -1.0));
+        assert!(contact.penetration.abs() < 1e-4);
+
+        assert!(collide(&diagonal, &aabb(0.0, 0.0, 1.0)).is_some());
+        assert!(collide(&segment(20.0, 0.0, 30.0, 0.0), &triangle(0.0, 0.0)).is_none());
+        assert!(collide(&segment(-5.0, 2.0, 5.0, 2.0), &triangle(0.0, 0.0)).is_some());
+    }
+
+    #[test]
+    fn rays_hit_the_nearest_surface() {
+        let right = Vector2::new(1.0, 0.0);
+
+        let hit = ray_cast(&circle(20.0, 0.0, 5.0), Vector2::zero(), right, 100.0).unwrap();
+        assert!((hit.distance - 15.0).abs() < 1e-4);
+        assert_near(hit.normal, Vector2::new(-1.0, 0.0));
+
+        let hit = ray_cast(&aabb(20.0, 0.0, 5.0), Vector2::zero(), right, 100.0).unwrap();
+        assert_near(hit.point, Vector2::new(15.0, 0.0));
+        assert_near(hit.normal, Vector2::new(-1.0, 0.0));
+
+        let hit = ray_cast(&segment(10.0, -5.0, 10.0, 5.0), Vector2::zero(), right, 100.0).unwrap();
+        assert!((hit.distance - 10.0).abs() < 1e-4);
+        assert_near(hit.normal, Vector2::new(-1.0, 0.0));
+
+        let hit = ray_cast(&triangle(-5.0, -5.0), Vector2::zero(), right, 100.0).unwrap();
+        assert_eq!(hit.distance, 0.0);
+
+        assert!(ray_cast(&circle(20.0, 0.0, 5.0), Vector2::zero(), right, 10.0).is_none());
+        assert!(ray_cast(&circle(-20.0, 0.0, 5.0), Vector2::zero(), right, 100.0).is_none());
+        assert!(ray_cast(&aabb(20.0, 20.0, 5.0), Vector2::zero(), right, 100.0).is_none());
+    }
+}
@@ -0,0 +1,217 @@
+use crate::actor::ActorId;
+use crate::collision_world::{ContactEvent, ContactKind};
+use crate::game::Game;
+use crate::input_component::InputComponent;
+use crate::math::Vector2;
+use crate::move_component::MoveComponent;
+
+// What the response needs to know about one side of a contact
+struct Mover {
+    inverse_mass: f32,
+    velocity: Vector2,
+    restitution: f32,
+}
+
+/// Push apart and bounce every overlapping pair whose actors both have
+/// collision response on. Actors without a MoveComponent are immovable.
+pub fn resolve_contacts(game: &mut Game, events: &[ContactEvent]) {
+    for event in events.iter().filter(|event| event.kind != ContactKind::Exit) {
+        let responsive = |id: ActorId| {
+            game.get_actor(id)
+                .is_some_and(|actor| actor.has_collision_response() && !actor.is_dead())
+        };
+        if event.actor_a == event.actor_b || !responsive(event.actor_a) || !responsive(event.actor_b) {
+            continue;
+        }
+
+        let a = get_mover(game, event.actor_a);
+        let b = get_mover(game, event.actor_b);
+        let inverse_mass_sum = a.inverse_mass + b.inverse_mass;
+        if inverse_mass_sum <= 0.0 {
+            continue;
+        }
+        let normal = event.normal;
+
+        // Positional separation, shared by inverse mass
+        let correction = normal * (event.penetration / inverse_mass_sum);
+        move_actor(game, event.actor_a, correction * -a.inverse_mass);
+        move_actor(game, event.actor_b, correction * b.inverse_mass);
+
+        // Only bounce if they're moving towards each other
+        let relative = b.velocity - a.velocity;
+        let normal_speed = Vector2::dot(relative, normal);
+        if normal_speed >= 0.0 {
+            continue;
+        }
+
+        let restitution = a.restitution.min(b.restitution);
+        if restitution >= 1.0 && b.inverse_mass <= 0.0 {
+            // Elastic bounce off something immovable is a plain reflection
+            set_velocity(game, event.actor_a, Vector2::reflect(a.velocity, normal));
+        } else if restitution >= 1.0 && a.inverse_mass <= 0.0 {
+            set_velocity(game, event.actor_b, Vector2::reflect(b.velocity, normal));
+        } else {
+            let impulse = normal * (-(1.0 + restitution) * normal_speed / inverse_mass_sum);
+            set_velocity(game, event.actor_a, a.velocity - impulse * a.inverse_mass);
+            set_velocity(game, event.actor_b, b.velocity + impulse * b.inverse_mass);
+        }
+    }
+}
+
+// Run `f` on the actor's MoveComponent, or the one inside its InputComponent
+fn with_move_component<R>(game: &mut Game, actor: ActorId, f: impl FnOnce(&mut MoveComponent) -> R) -> Option<R> {
+    let components = game.get_actor(actor)?.get_components();
+    for id in components {
+        if game.get_component::<MoveComponent>(id).is_some() {
+            return game.get_component_mut::<MoveComponent>(id).map(f);
+        }
+        if let Some(input) = game.get_component_mut::<InputComponent>(id) {
+            return Some(f(input.get_move_component_mut()));
+        }
+    }
+    None
+}
+
+fn get_mover(game: &mut Game, actor: ActorId) -> Mover {
+    let forward = game
+        .get_actor(actor)
+        .map_or(Vector2::zero(), |owner| owner.get_forward());
+    with_move_component(game, actor, |move_component| Mover {
+        inverse_mass: 1.0 / move_component.get_mass(),
+        velocity: move_component.get_current_velocity(forward),
+        restitution: move_component.get_restitution(),
+    })
+    .unwrap_or(Mover {
+        inverse_mass: 0.0,
+        velocity: Vector2::zero(),
+        restitution: 1.0,
+    })
+}
+
+fn move_actor(game: &mut Game, actor: ActorId, offset: Vector2) {
+    if let Some(owner) = game.get_actor_mut(actor) {
+        owner.set_position(owner.get_position() + offset);
+    }
+}
+
+fn set_velocity(game: &mut Game, actor: ActorId, velocity: Vector2) {
+    let heading = with_move_component(game, actor, |move_component| {
+        move_component.set_current_velocity(velocity)
+    })
+    .flatten();
+    if let (Some(heading), Some(owner)) = (heading, game.get_actor_mut(actor)) {
+        owner.set_rotation(heading);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actor::Actor;
+    use crate::asteroid::{Asteroid, AsteroidSize, SplitRules};
+    use crate::circle_component::CircleComponent;
+    use crate::component::ComponentId;
+    use crate::game::GameConfig;
+    use crate::math::math;
+
+    fn headless_game() -> Game {
+        Game::with_config(GameConfig::headless())
+    }
+
+    // A responsive ball of radius 10 moving at `velocity` in physics mode
+    fn ball(game: &mut Game, position: Vector2, velocity: Vector2, restitution: f32) -> (ActorId, ComponentId) {
+        let actor = Actor::new(game);
+        let owner = game.get_actor_mut(actor).unwrap();
+        owner.set_position(position);
+        owner.set_collision_response(true);
+        let circle = CircleComponent::new(game, actor);
+        game.get_component_mut::<CircleComponent>(circle).unwrap().set_radius(10.0);
+        let move_component = MoveComponent::new(game, actor, 10);
+        let move_comp = game.get_component_mut::<MoveComponent>(move_component).unwrap();
+        move_comp.set_physics_enabled(true);
+        move_comp.set_velocity(velocity);
+        move_comp.set_restitution(restitution);
+        (actor, move_component)
+    }
+
+    fn velocity(game: &Game, move_component: ComponentId) -> Vector2 {
+        game.get_component::<MoveComponent>(move_component).unwrap().get_velocity()
+    }
+
+    fn distance(game: &Game, a: ActorId, b: ActorId) -> f32 {
+        (game.get_actor(a).unwrap().get_position() - game.get_actor(b).unwrap().get_position()).length()
+    }
+
+    #[test]
+    fn equal_masses_swap_velocities_in_an_elastic_collision() {
+        let mut game = headless_game();
+        let (a, move_a) = ball(&mut game, Vector2::new(0.0, 0.0), Vector2::new(100.0, 0.0), 1.0);
+        let (b, move_b) = ball(&mut game, Vector2::new(16.0, 0.0), Vector2::new(-50.0, 0.0), 1.0);
+
+        game.update_game(0.0);
+        assert!((distance(&game, a, b) - 20.0).abs() < 1e-3);
+        assert!((velocity(&game, move_a) - Vector2::new(-50.0, 0.0)).length() < 1e-3);
+        assert!((velocity(&game, move_b) - Vector2::new(100.0, 0.0)).length() < 1e-3);
+    }
+
+    #[test]
+    fn inelastic_collision_keeps_momentum_only() {
+        let mut game = headless_game();
+        let (_, move_a) = ball(&mut game, Vector2::new(0.0, 0.0), Vector2::new(100.0, 0.0), 0.0);
+        let (_, move_b) = ball(&mut game, Vector2::new(16.0, 0.0), Vector2::zero(), 0.0);
+        game.get_component_mut::<MoveComponent>(move_b).unwrap().set_mass(3.0);
+
+        game.update_game(0.0);
+        // 1 * 100 = (1 + 3) * v
+        assert!((velocity(&game, move_a) - Vector2::new(25.0, 0.0)).length() < 1e-3);
+        assert!((velocity(&game, move_b) - Vector2::new(25.0, 0.0)).length() < 1e-3);
+    }
+
+    #[test]
+    fn immovable_actor_reflects_the_other() {
+        let mut game = headless_game();
+        let (ball_actor, move_component) =
+            ball(&mut game, Vector2::new(0.0, 15.0), Vector2::new(30.0, -40.0), 1.0);
+        let wall = Actor::new(&mut game);
+        game.get_actor_mut(wall).unwrap().set_collision_response(true);
+        let circle = CircleComponent::new(&mut game, wall);
+        game.get_component_mut::<CircleComponent>(circle).unwrap().set_radius(10.0);
+
+        game.update_game(0.0);
+        assert!((velocity(&game, move_component) - Vector2::new(30.0, 40.0)).length() < 1e-3);
+        assert!((distance(&game, ball_actor, wall) - 20.0).abs() < 1e-3);
+        assert_eq!(game.get_actor(wall).unwrap().get_position(), Vector2::zero());
+    }
+
+    #[test]
+    fn response_is_opt_in_per_actor() {
+        let mut game = headless_game();
+        let (_, move_a) = ball(&mut game, Vector2::new(0.0, 0.0), Vector2::new(100.0, 0.0), 1.0);
+        let (b, _) = ball(&mut game, Vector2::new(16.0, 0.0), Vector2::zero(), 1.0);
+        game.get_actor_mut(b).unwrap().set_collision_response(false);
+
+        game.update_game(0.0);
+        assert_eq!(velocity(&game, move_a), Vector2::new(100.0, 0.0));
+        assert_eq!(game.get_actor(b).unwrap().get_position(), Vector2::new(16.0, 0.0));
+    }
+
+    #[test]
+    fn bouncing_asteroids_turn_around() {
+        let mut game = headless_game();
+        game.set_split_rules(SplitRules {
+            bounce: true,
+            ..SplitRules::default()
+        });
+        // Outlines reach at least 30 from the center, so these overlap
+        let left = Asteroid::with_size(&mut game, AsteroidSize::Large, Vector2::new(-25.0, 0.0), 0.0, 100.0);
+        let right = Asteroid::with_size(&mut game, AsteroidSize::Large, Vector2::new(25.0, 0.0), math::PI, 100.0);
+
+        // The outlines are irregular, so the bounce isn't exactly head-on
+        game.update_game(0.0);
+        let left_actor = game.get_actor(left.get_actor()).unwrap();
+        let right_actor = game.get_actor(right.get_actor()).unwrap();
+        assert!(left_actor.get_forward().x < 0.0);
+        assert!(right_actor.get_forward().x > 0.0);
+        assert!(distance(&game, left.get_actor(), right.get_actor()) > 50.0);
+    }
+}
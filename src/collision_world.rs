@@ -0,0 +1,598 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use crate::actor::ActorId;
+use crate::circle_component::CircleComponent;
+use crate::collider_component::ColliderComponent;
+use crate::collision::{collide, ray_cast, Shape};
+use crate::component::{Component, ComponentId};
+use crate::game::Game;
+use crate::math::Vector2;
+use crate::world_bounds::WorldBounds;
+
+/// Collision layer bits. A pair is tested only when each collider's layer
+/// is in the other's mask.
+pub mod layers {
+    pub const DEFAULT: u32 = 1 << 0;
+    pub const SHIP: u32 = 1 << 1;
+    pub const LASER: u32 = 1 << 2;
+    pub const ASTEROID: u32 = 1 << 3;
+    pub const ALL: u32 = u32::MAX;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContactKind {
+    Enter,
+    Stay,
+    Exit,
+}
+
+/// Change in overlap between two colliders during the last collision step.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ContactEvent {
+    pub kind: ContactKind,
+    pub a: ComponentId,
+    pub b: ComponentId,
+    pub actor_a: ActorId,
+    pub actor_b: ActorId,
+    /// Unit vector from `a` towards `b`; zero on `Exit`.
+    pub normal: Vector2,
+    /// Overlap depth along `normal`; zero on `Exit`.
+    pub penetration: f32,
+}
+
+impl ContactEvent {
+    // The collider on the other side of the contact from `collider`
+    pub fn get_other(&self, collider: ComponentId) -> Option<ComponentId> {
+        if self.a == collider {
+            Some(self.b)
+        } else if self.b == collider {
+            Some(self.a)
+        } else {
+            None
+        }
+    }
+}
+
+/// Nearest collider found by `CollisionWorld::raycast`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RaycastHit {
+    pub collider: ComponentId,
+    pub actor: ActorId,
+    pub point: Vector2,
+    /// Surface normal at `point`, facing back against the ray.
+    pub normal: Vector2,
+    pub distance: f32,
+}
+
+// Snapshot of one collider taken at the start of a step
+struct Body {
+    id: ComponentId,
+    owner: ActorId,
+    shape: Shape,
+    layer: u32,
+    mask: u32,
+}
+
+impl Body {
+    // Works for every collider component type
+    fn from_component(game: &Game, id: ComponentId) -> Option<Body> {
+        let (owner, shape, layer, mask) =
+            if let Some(circle) = game.get_component::<CircleComponent>(id) {
+                (circle.get_owner(), circle.get_shape(game), circle.get_layer(), circle.get_mask())
+            } else {
+                let collider = game.get_component::<ColliderComponent>(id)?;
+                (
+                    collider.get_owner(),
+                    collider.get_world_shape(game)?,
+                    collider.get_layer(),
+                    collider.get_mask(),
+                )
+            };
+        game.get_actor(owner).filter(|actor| !actor.is_dead())?;
+        Some(Body {
+            id,
+            owner,
+            shape,
+            layer,
+            mask,
+        })
+    }
+}
+
+/// Broad phase over every registered `CircleComponent` and
+/// `ColliderComponent`, using a uniform
+/// grid hashed by cell. When wrapping is on, cells and distances wrap
+/// around the world bounds so actors on opposite edges still touch.
+pub struct CollisionWorld {
+    // Registration order, used to order contact pairs consistently
+    colliders: Vec<ComponentId>,
+    cell_size: f32,
+    wrap: bool,
+    // Ordered so Exit events come out the same on every run
+    contacts: BTreeMap<(ComponentId, ComponentId), (ActorId, ActorId)>,
+    events: Vec<ContactEvent>,
+}
+
+impl CollisionWorld {
+    pub fn new() -> Self {
+        CollisionWorld {
+            colliders: Vec::new(),
+            cell_size: 128.0,
+            wrap: true,
+            contacts: BTreeMap::new(),
+            events: Vec::new(),
+        }
+    }
+
+    pub fn add_collider(&mut self, collider: ComponentId) {
+        if !self.colliders.contains(&collider) {
+            self.colliders.push(collider);
+        }
+    }
+
+    // Its contacts are reported as exits on the next step
+    pub fn remove_collider(&mut self, collider: ComponentId) {
+        self.colliders.retain(|&c| c != collider);
+    }
+
+    // Roughly the diameter of the common collider works best
+    pub fn set_cell_size(&mut self, cell_size: f32) {
+        self.cell_size = cell_size.max(1.0);
+    }
+
+    pub fn set_wrapping(&mut self, wrap: bool) {
+        self.wrap = wrap;
+    }
+
+    /// Events from the last step: one `Enter` or `Stay` per overlapping
+    /// pair, and an `Exit` for every pair that stopped overlapping.
+    pub fn get_events(&self) -> &[ContactEvent] {
+        &self.events
+    }
+
+    /// Colliders currently overlapping `collider`, in a stable order.
+    pub fn get_contacts(&self, collider: ComponentId) -> impl Iterator<Item = ComponentId> + '_ {
+        self.events
+            .iter()
+            .filter(|event| event.kind != ContactKind::Exit)
+            .filter_map(move |event| event.get_other(collider))
+    }
+
+    /// Nearest collider on `layer_mask` hit by a ray from `origin` along
+    /// `direction` within `max_distance`. Uses the colliders' current
+    /// positions and does not wrap around the world edges.
+    pub fn raycast(
+        &self,
+        game: &Game,
+        origin: Vector2,
+        direction: Vector2,
+        max_distance: f32,
+        layer_mask: u32,
+    ) -> Option<RaycastHit> {
+        let length = direction.length();
+        if length <= f32::EPSILON {
+            return None;
+        }
+        let direction = direction * (1.0 / length);
+
+        self.colliders
+            .iter()
+            .filter_map(|&id| Body::from_component(game, id))
+            .filter(|body| body.layer & layer_mask != 0)
+            .filter_map(|body| {
+                let hit = ray_cast(&body.shape, origin, direction, max_distance)?;
+                Some(RaycastHit {
+                    collider: body.id,
+                    actor: body.owner,
+                    point: hit.point,
+                    normal: hit.normal,
+                    distance: hit.distance,
+                })
+            })
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+    }
+
+    /// Nearest collider on `layer_mask` touched by the segment from
+    /// `start` to `end`.
+    pub fn segment_query(&self, game: &Game, start: Vector2, end: Vector2, layer_mask: u32) -> Option<RaycastHit> {
+        let diff = end - start;
+        self.raycast(game, start, diff, diff.length(), layer_mask)
+    }
+
+    /// Recompute all overlaps from the colliders' current positions.
+    pub fn update(&mut self, game: &Game) {
+        // Colliders whose component went away are dropped here
+        self.colliders.retain(|&id| {
+            game.get_component::<CircleComponent>(id).is_some()
+                || game.get_component::<ColliderComponent>(id).is_some()
+        });
+        let bodies: Vec<Body> = self
+            .colliders
+            .iter()
+            .filter_map(|&id| Body::from_component(game, id))
+            .collect();
+        let bounds = *game.get_world_bounds();
+
+        let mut current = BTreeMap::new();
+        self.events.clear();
+        for (i, j) in self.candidate_pairs(&bodies, &bounds) {
+            let (a, b) = (&bodies[i], &bodies[j]);
+            if a.layer & b.mask == 0 || b.layer & a.mask == 0 {
+                continue;
+            }
+
+            // Test against b's closest copy when the world wraps
+            let center_a = a.shape.get_center();
+            let center_b = b.shape.get_center();
+            let offset = center_a - self.delta(center_a, center_b, &bounds) - center_b;
+            let contact = if offset.length_sq() > 0.0 {
+                collide(&a.shape, &b.shape.translated(offset))
+            } else {
+                collide(&a.shape, &b.shape)
+            };
+
+            if let Some(contact) = contact {
+                let key = (a.id, b.id);
+                let kind = if self.contacts.contains_key(&key) {
+                    ContactKind::Stay
+                } else {
+                    ContactKind::Enter
+                };
+                self.events.push(ContactEvent {
+                    kind,
+                    a: a.id,
+                    b: b.id,
+                    actor_a: a.owner,
+                    actor_b: b.owner,
+                    normal: contact.normal,
+                    penetration: contact.penetration,
+                });
+                current.insert(key, (a.owner, b.owner));
+            }
+        }
+
+        for (&(a, b), &(actor_a, actor_b)) in &self.contacts {
+            if !current.contains_key(&(a, b)) {
+                self.events.push(ContactEvent {
+                    kind: ContactKind::Exit,
+                    a,
+                    b,
+                    actor_a,
+                    actor_b,
+                    normal: Vector2::zero(),
+                    penetration: 0.0,
+                });
+            }
+        }
+        self.contacts = current;
+    }
+
+    // Pairs (i < j) sharing at least one grid cell
+    fn candidate_pairs(&self, bodies: &[Body], bounds: &WorldBounds) -> Vec<(usize, usize)> {
+        // Cells are stretched so a whole number of them spans the world;
+        // a position past one edge then hashes into the cell on the other
+        let columns = (bounds.get_width() / self.cell_size).floor().max(1.0);
+        let rows = (bounds.get_height() / self.cell_size).floor().max(1.0);
+        let cell_width = (bounds.get_width() / columns).max(1.0);
+        let cell_height = (bounds.get_height() / rows).max(1.0);
+        let (columns, rows) = (columns as i32, rows as i32);
+        let min = bounds.get_min();
+
+        let mut grid: HashMap<(i32, i32), Vec<usize>> = HashMap::new();
+        for (index, body) in bodies.iter().enumerate() {
+            let cell_of = |v: f32, origin: f32, size: f32| ((v - origin) / size).floor() as i32;
+            let (body_min, body_max) = body.shape.get_bounds();
+            let x0 = cell_of(body_min.x, min.x, cell_width);
+            let x1 = cell_of(body_max.x, min.x, cell_width);
+            let y0 = cell_of(body_min.y, min.y, cell_height);
+            let y1 = cell_of(body_max.y, min.y, cell_height);
+
+            let mut cells = HashSet::new();
+            for x in x0..=x1.min(x0 + columns - 1) {
+                for y in y0..=y1.min(y0 + rows - 1) {
+                    let cell = if self.wrap {
+                        (x.rem_euclid(columns), y.rem_euclid(rows))
+                    } else {
+                        (x, y)
+                    };
+                    cells.insert(cell);
+                }
+            }
+            for cell in cells {
+                grid.entry(cell).or_default().push(index);
+            }
+        }
+
+        let mut seen = HashSet::new();
+        let mut pairs = Vec::new();
+        for members in grid.values() {
+            for (n, &i) in members.iter().enumerate() {
+                for &j in &members[n + 1..] {
+                    let pair = (i.min(j), i.max(j));
+                    if seen.insert(pair) {
+                        pairs.push(pair);
+                    }
+                }
+            }
+        }
+        // Keep events in a stable order
+        pairs.sort_unstable();
+        pairs
+    }
+
+    // Shortest vector from b to a, across the edges when wrapping
+    fn delta(&self, a: Vector2, b: Vector2, bounds: &WorldBounds) -> Vector2 {
+        let mut diff = a - b;
+        if self.wrap {
+            let width = bounds.get_width();
+            let height = bounds.get_height();
+            diff.x -= width * (diff.x / width).round();
+            diff.y -= height * (diff.y / height).round();
+        }
+        diff
+    }
+}
+
+impl Default for CollisionWorld {
+    fn default() -> Self {
+        CollisionWorld::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actor::Actor;
+    use crate::game::GameConfig;
+    use crate::math::math;
+
+    fn headless_game() -> Game {
+        Game::with_config(GameConfig::headless())
+    }
+
+    fn collider(game: &mut Game, position: Vector2, radius: f32) -> (ActorId, ComponentId) {
+        let actor = Actor::new(game);
+        game.get_actor_mut(actor).unwrap().set_position(position);
+        let circle = CircleComponent::new(game, actor);
+        game.get_component_mut::<CircleComponent>(circle).unwrap().set_radius(radius);
+        (actor, circle)
+    }
+
+    fn kinds(game: &Game) -> Vec<ContactKind> {
+        game.get_collision_world().get_events().iter().map(|event| event.kind).collect()
+    }
+
+    #[test]
+    fn contacts_enter_stay_and_exit() {
+        let mut game = headless_game();
+        let (_, a) = collider(&mut game, Vector2::new(0.0, 0.0), 10.0);
+        let (mover, b) = collider(&mut game, Vector2::new(15.0, 0.0), 10.0);
+
+        game.update_game(0.01);
+        assert_eq!(kinds(&game), vec![ContactKind::Enter]);
+        let event = game.get_collision_world().get_events()[0];
+        assert_eq!((event.a, event.b, event.actor_b), (a, b, mover));
+        assert_eq!(game.get_collision_world().get_contacts(a).collect::<Vec<_>>(), vec![b]);
+
+        game.update_game(0.01);
+        assert_eq!(kinds(&game), vec![ContactKind::Stay]);
+
+        game.get_actor_mut(mover).unwrap().set_position(Vector2::new(100.0, 0.0));
+        game.update_game(0.01);
+        assert_eq!(kinds(&game), vec![ContactKind::Exit]);
+        assert_eq!(game.get_collision_world().get_contacts(a).count(), 0);
+
+        game.update_game(0.01);
+        assert!(kinds(&game).is_empty());
+    }
+
+    #[test]
+    fn removed_collider_exits_its_contacts() {
+        let mut game = headless_game();
+        collider(&mut game, Vector2::new(0.0, 0.0), 10.0);
+        let (doomed, _) = collider(&mut game, Vector2::new(5.0, 0.0), 10.0);
+
+        game.update_game(0.01);
+        game.remove_actor(doomed);
+        game.update_game(0.01);
+        assert_eq!(kinds(&game), vec![ContactKind::Exit]);
+        assert_eq!(game.get_collision_world().get_events()[0].actor_b, doomed);
+    }
+
+    #[test]
+    fn exits_come_out_in_a_stable_order() {
+        let mut game = headless_game();
+        let pairs: Vec<(ActorId, ComponentId, ComponentId)> = (0..6)
+            .map(|i| {
+                let x = i as f32 * 100.0 - 300.0;
+                let (_, a) = collider(&mut game, Vector2::new(x, 0.0), 10.0);
+                let (mover, b) = collider(&mut game, Vector2::new(x, 5.0), 10.0);
+                (mover, a, b)
+            })
+            .collect();
+        game.update_game(0.01);
+
+        for &(mover, _, _) in &pairs {
+            let position = game.get_actor(mover).unwrap().get_position();
+            game.get_actor_mut(mover).unwrap().set_position(position + Vector2::new(0.0, 200.0));
+        }
+        game.update_game(0.01);
+        let exits: Vec<(ComponentId, ComponentId)> = game
+            .get_collision_world()
+            .get_events()
+            .iter()
+            .map(|event| (event.a, event.b))
+            .collect();
+        let expected: Vec<(ComponentId, ComponentId)> = pairs.iter().map(|&(_, a, b)| (a, b)).collect();
+        assert_eq!(kinds(&game), vec![ContactKind::Exit; 6]);
+        assert_eq!(exits, expected);
+    }
+
+    #[test]
+    fn contacts_wrap_across_world_edges() {
+        let mut game = headless_game();
+        // 1024x768 world centered on the origin
+        collider(&mut game, Vector2::new(-510.0, 0.0), 10.0);
+        collider(&mut game, Vector2::new(508.0, 0.0), 10.0);
+
+        game.update_game(0.01);
+        assert_eq!(kinds(&game), vec![ContactKind::Enter]);
+
+        game.get_collision_world_mut().set_wrapping(false);
+        game.update_game(0.01);
+        assert_eq!(kinds(&game), vec![ContactKind::Exit]);
+    }
+
+    #[test]
+    fn wrapping_works_when_cells_dont_divide_the_world() {
+        let mut game = headless_game();
+        // 1000 wide with 128 cells: 7.8 cells across
+        game.set_world_bounds(WorldBounds::from_size(1000.0, 700.0));
+        game.get_collision_world_mut().set_cell_size(128.0);
+        // Just past the right edge, 7 from the other collider once wrapped
+        collider(&mut game, Vector2::new(503.0, 0.0), 5.0);
+        collider(&mut game, Vector2::new(-490.0, 0.0), 10.0);
+
+        game.update_game(0.01);
+        assert_eq!(kinds(&game), vec![ContactKind::Enter]);
+
+        // Same across the top edge
+        let mut game = headless_game();
+        game.set_world_bounds(WorldBounds::from_size(1000.0, 700.0));
+        collider(&mut game, Vector2::new(0.0, 352.0), 5.0);
+        collider(&mut game, Vector2::new(0.0, -340.0), 10.0);
+        game.update_game(0.01);
+        assert_eq!(kinds(&game), vec![ContactKind::Enter]);
+    }
+
+    #[test]
+    fn layers_and_masks_filter_pairs() {
+        let mut game = headless_game();
+        let (_, rock) = collider(&mut game, Vector2::zero(), 10.0);
+        let (_, other_rock) = collider(&mut game, Vector2::zero(), 10.0);
+        let (_, laser) = collider(&mut game, Vector2::zero(), 10.0);
+        for circle in [rock, other_rock] {
+            let circle = game.get_component_mut::<CircleComponent>(circle).unwrap();
+            circle.set_layer(layers::ASTEROID);
+            circle.set_mask(layers::LASER);
+        }
+        let laser_circle = game.get_component_mut::<CircleComponent>(laser).unwrap();
+        laser_circle.set_layer(layers::LASER);
+        laser_circle.set_mask(layers::ASTEROID);
+
+        game.update_game(0.01);
+        let world = game.get_collision_world();
+        assert_eq!(world.get_contacts(laser).collect::<Vec<_>>(), vec![rock, other_rock]);
+        assert_eq!(world.get_contacts(rock).collect::<Vec<_>>(), vec![laser]);
+    }
+
+    #[test]
+    fn collider_shapes_follow_their_owner() {
+        use crate::collider_component::{ColliderComponent, ColliderShape};
+
+        let mut game = headless_game();
+        let (_, circle) = collider(&mut game, Vector2::new(0.0, 25.0), 10.0);
+        let beam = Actor::new(&mut game);
+        let segment = ColliderComponent::new(&mut game, beam, ColliderShape::Segment {
+            start: Vector2::zero(),
+            end: Vector2::new(30.0, 0.0),
+        });
+
+        // Pointing right: misses the circle above
+        game.update_game(0.01);
+        assert!(kinds(&game).is_empty());
+
+        // Turned to point up: passes through it
+        game.get_actor_mut(beam).unwrap().set_rotation(math::PI_OVER_2);
+        game.update_game(0.01);
+        let event = game.get_collision_world().get_events()[0];
+        assert_eq!((event.kind, event.a, event.b), (ContactKind::Enter, circle, segment));
+        assert!((event.normal - Vector2::new(-1.0, 0.0)).length() < 1e-4);
+        assert!((event.penetration - 10.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn only_oriented_boxes_turn_with_their_owner() {
+        use crate::collider_component::{ColliderComponent, ColliderShape};
+
+        let mut game = headless_game();
+        collider(&mut game, Vector2::new(0.0, 13.0), 2.0);
+        let crate_actor = Actor::new(&mut game);
+        game.get_actor_mut(crate_actor).unwrap().set_rotation(math::PI / 4.0);
+        let half_extents = Vector2::new(10.0, 10.0);
+        let collider = ColliderComponent::new(&mut game, crate_actor, ColliderShape::Box { half_extents });
+
+        // Still axis-aligned: the top edge at y = 10 stays clear of the circle
+        game.update_game(0.01);
+        assert!(kinds(&game).is_empty());
+
+        // Turned into a diamond: its corner reaches y = 14.1
+        game.get_component_mut::<ColliderComponent>(collider)
+            .unwrap()
+            .set_shape(ColliderShape::OrientedBox { half_extents });
+        game.update_game(0.01);
+        assert_eq!(kinds(&game), vec![ContactKind::Enter]);
+    }
+
+    #[test]
+    fn raycast_returns_the_nearest_hit_on_the_mask() {
+        let mut game = headless_game();
+        let (far, _) = collider(&mut game, Vector2::new(100.0, 0.0), 10.0);
+        let (near, near_circle) = collider(&mut game, Vector2::new(50.0, 0.0), 10.0);
+        let (_, behind) = collider(&mut game, Vector2::new(-50.0, 0.0), 10.0);
+        game.get_component_mut::<CircleComponent>(behind).unwrap().set_layer(layers::SHIP);
+
+        let hit = game.raycast(Vector2::zero(), Vector2::new(2.0, 0.0), 500.0, layers::ALL).unwrap();
+        assert_eq!((hit.actor, hit.collider), (near, near_circle));
+        assert!((hit.distance - 40.0).abs() < 1e-4);
+        assert!((hit.point - Vector2::new(40.0, 0.0)).length() < 1e-4);
+        assert!((hit.normal - Vector2::new(-1.0, 0.0)).length() < 1e-4);
+
+        // Filtered by layer, then by distance
+        game.get_component_mut::<CircleComponent>(near_circle).unwrap().set_layer(layers::SHIP);
+        let hit = game.raycast(Vector2::zero(), Vector2::new(1.0, 0.0), 500.0, layers::DEFAULT).unwrap();
+        assert_eq!(hit.actor, far);
+        assert!(game.raycast(Vector2::zero(), Vector2::new(1.0, 0.0), 60.0, layers::DEFAULT).is_none());
+
+        assert!(game.raycast(Vector2::zero(), Vector2::new(-1.0, 0.0), 500.0, layers::DEFAULT).is_none());
+        let hit = game.raycast(Vector2::zero(), Vector2::new(-1.0, 0.0), 500.0, layers::SHIP).unwrap();
+        assert!((hit.distance - 40.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn segment_query_stops_at_its_end() {
+        let mut game = headless_game();
+        let (target, _) = collider(&mut game, Vector2::new(0.0, 50.0), 10.0);
+
+        assert!(game.segment_query(Vector2::zero(), Vector2::new(0.0, 30.0), layers::ALL).is_none());
+        let hit = game.segment_query(Vector2::zero(), Vector2::new(0.0, 45.0), layers::ALL).unwrap();
+        assert_eq!(hit.actor, target);
+        assert!((hit.distance - 40.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn grid_finds_the_same_pairs_as_brute_force() {
+        let mut game = headless_game();
+        let mut circles = Vec::new();
+        for i in 0..200 {
+            let x = ((i * 37) % 1000) as f32 - 500.0;
+            let y = ((i * 53) % 740) as f32 - 370.0;
+            circles.push(collider(&mut game, Vector2::new(x, y), 5.0 + (i % 4) as f32 * 10.0));
+        }
+        game.get_collision_world_mut().set_wrapping(false);
+        game.get_collision_world_mut().set_cell_size(64.0);
+        game.update_game(0.01);
+
+        let mut expected = 0;
+        for (n, &(_, a)) in circles.iter().enumerate() {
+            for &(_, b) in &circles[n + 1..] {
+                let a = game.get_component::<CircleComponent>(a).unwrap();
+                let b = game.get_component::<CircleComponent>(b).unwrap();
+                if crate::circle_component::intersect(&game, a, b) {
+                    expected += 1;
+                }
+            }
+        }
+        assert!(expected > 0);
+        assert_eq!(game.get_collision_world().get_events().len(), expected);
+    }
+}
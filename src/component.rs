@@ -1,58 +1,58 @@
-use std::rc::Rc;
-use std::cell::RefCell;
-use crate::actor::Actor;
+use std::any::Any;
+use crate::actor::ActorId;
+use crate::game::Game;
+use crate::input_map::InputState;
+use crate::registry::Handle;
+
+pub type ComponentId = Handle<Box<dyn Component>>;
+
+// Lets Game hand back concrete component types from its registry
+pub trait AsAny {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Any> AsAny for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
 
-pub trait Component {
-    fn new(owner: Rc<RefCell<Actor>>, update_order: i32) -> Self
-    where
-        Self: Sized;
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
 
-    fn process_input(&mut self, _key_state: &[u8]) {}
+// Components live in Game's registry and are checked out while they run,
+// so they get the whole game and reach their owner through its ActorId.
+pub trait Component: AsAny + 'static {
+    fn process_input(&mut self, _game: &mut Game, _input: &InputState) {}
 
-    fn update(&mut self, delta_time: f32);
+    fn update(&mut self, game: &mut Game, delta_time: f32);
 
     fn get_update_order(&self) -> i32;
+
+    fn get_owner(&self) -> ActorId;
 }
 
-#[derive(Clone)]
+#[derive(Clone, Copy)]
 pub struct BaseComponent {
-    pub owner: Rc<RefCell<Actor>>,
+    pub owner: ActorId,
     pub update_order: i32,
 }
 
 impl BaseComponent {
-    pub fn new(owner: Rc<RefCell<Actor>>, update_order: i32) -> Self {
-        let component = BaseComponent {
-            owner: owner.clone(),
-            update_order,
-        };
-
-        // Add to actor's vector of components
-        owner.borrow_mut().add_component(Rc::new(RefCell::new(component.clone())));
-
-        component
-    }
-}
-
-impl Component for BaseComponent {
-    fn new(owner: Rc<RefCell<Actor>>, update_order: i32) -> Self {
+    pub fn new(owner: ActorId, update_order: i32) -> Self {
         BaseComponent {
             owner,
             update_order,
         }
     }
 
-    fn update(&mut self, _delta_time: f32) {
-        // Default implementation for the update method.
-    }
-
-    fn get_update_order(&self) -> i32 {
+    pub fn get_update_order(&self) -> i32 {
         self.update_order
     }
-}
 
-impl Drop for BaseComponent {
-    fn drop(&mut self) {
-        self.owner.borrow_mut().remove_component(Rc::new(RefCell::new(self.clone())));
+    pub fn get_owner(&self) -> ActorId {
+        self.owner
     }
 }
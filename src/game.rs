@@ -1,41 +1,237 @@
 extern crate sdl2;
 extern crate gl;
 use sdl2::video::GLProfile;
-use sdl2::event::Event;
+use sdl2::event::{Event, WindowEvent};
 use sdl2::keyboard::Scancode;
-use sdl2::render::WindowCanvas;
-use sdl2::render::TextureCreator;
-use sdl2::video::WindowContext;
-use std::time::Duration;
+use sdl2::video::{GLContext, SwapInterval, Window};
+use sdl2::Sdl;
+use std::time::{Duration, Instant};
 use std::collections::HashMap;
 use std::rc::Rc;
 use std::cell::RefCell;
+use std::sync::Arc;
 
 
-use crate::actor::Actor;
-use crate::asteroid::Asteroid;
+use crate::actor::{Actor, ActorId};
+use crate::asteroid::{Asteroid, SplitRules};
+use crate::audio::{Audio, AudioOutput, Channel, Sound, VoiceId};
+use crate::collision_response::resolve_contacts;
+use crate::collision_world::{CollisionWorld, RaycastHit};
+use crate::component::{Component, ComponentId};
+use crate::gamepad::Gamepads;
+use crate::input_map::{Action, InputMap, InputState};
+use crate::input_recording::{InputRecording, Replay};
+use crate::math::Vector2;
+use crate::random::Random;
+use crate::registry::Registry;
+use crate::shader::Shader;
+use crate::ship::{Ship, ShipConfig};
 use crate::texture::Texture;
 use crate::sprite_component::SpriteComponent;
+use crate::vertex_array::VertexArray;
+use crate::world_bounds::WorldBounds;
+
+/// Length of the key state array in `InputState`, one byte per SDL scancode.
+pub const NUM_SCANCODES: usize = sdl2::sys::SDL_Scancode::SDL_NUM_SCANCODES as usize;
+
+/// Settings used when constructing a `Game`.
+pub struct GameConfig {
+    pub title: String,
+    pub window_width: u32,
+    pub window_height: u32,
+    /// Skip SDL, the window and the GL context entirely. Actors, components
+    /// and collisions still update, but textures are never loaded and
+    /// nothing is drawn.
+    pub headless: bool,
+    /// Length of one simulation step in seconds. `update_game` always
+    /// receives exactly this delta so gameplay is deterministic. Must be
+    /// positive; anything else falls back to 1/60.
+    pub fixed_time_step: f32,
+    /// Upper bound on the measured frame time, so a stall (debugger,
+    /// window drag) doesn't trigger a burst of catch-up steps. Must be
+    /// finite, or it falls back to 0.05; raised to `fixed_time_step` if
+    /// smaller, or no step would ever run.
+    pub max_delta_time: f32,
+    /// Frame cap applied by sleeping at the end of each frame. `None`
+    /// runs uncapped. Ignored when `vsync` is on, since presenting already
+    /// blocks on the display.
+    pub target_fps: Option<u32>,
+    pub vsync: bool,
+    /// Fixed world rectangle. `None` derives it from the window size
+    /// (centered on the origin) and follows the window when it's resized.
+    pub world_bounds: Option<WorldBounds>,
+    /// Seed for the game's `Random`. `None` picks a fresh one each run.
+    pub seed: Option<u64>,
+    /// Keep every frame's input so the session can be saved with
+    /// `get_recording` and replayed later.
+    pub record_input: bool,
+    /// Play this recording back instead of reading the devices. Its seed,
+    /// time step and world bounds override the ones above.
+    pub replay: Option<InputRecording>,
+    /// Where sound goes. `AudioOutput::Sdl` needs a window; without one
+    /// it falls back to `Null`.
+    pub audio: AudioOutput,
+    /// Bindings for game-wide actions such as `Action::ToggleMute`. The
+    /// ship reads its own from `ShipConfig`.
+    pub input_map: InputMap,
+}
+
+impl GameConfig {
+    pub fn headless() -> Self {
+        GameConfig {
+            headless: true,
+            audio: AudioOutput::Null,
+            ..GameConfig::default()
+        }
+    }
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        GameConfig {
+            title: "Asteroid".to_string(),
+            window_width: 1024,
+            window_height: 768,
+            headless: false,
+            fixed_time_step: 1.0 / 60.0,
+            max_delta_time: 0.05,
+            target_fps: Some(60),
+            vsync: false,
+            world_bounds: None,
+            seed: None,
+            record_input: false,
+            replay: None,
+            audio: AudioOutput::Sdl,
+            input_map: InputMap::default(),
+        }
+    }
+}
+
+// Everything that only exists when a window is open
+struct Video {
+    sdl: Sdl,
+    window: Window,
+    context: GLContext,
+    // None if SDL's game controller subsystem failed to start
+    gamepads: Option<Gamepads>,
+}
 
 pub struct Game {
-    canvas: WindowCanvas,
-    context: sdl2::video::GLContext,
+    video: Option<Video>,
     is_running: bool,
     textures: HashMap<String, Rc<RefCell<Texture>>>,
-    actors: Vec<Rc<RefCell<Actor>>>,
-    pending_actors: Vec<Rc<RefCell<Actor>>>,
-    sprites: Vec<Rc<RefCell<SpriteComponent>>>,
-    // sprite_shader: Option<Shader>,
-    // sprite_verts: Option<VertexArray>,
-    asteroids: Vec<Rc<RefCell<Asteroid>>>,
-    window: Option<sdl2::video::Window>,
-    ticks_count: u32,
+    // None caches a sound that failed to load, so it isn't retried
+    sounds: HashMap<String, Option<Arc<Sound>>>,
+    audio: Audio,
+    input_map: InputMap,
+    // Whether ToggleMute was held last frame
+    mute_held: bool,
+    actors: Registry<Actor>,
+    components: Registry<Box<dyn Component>>,
+    // Update order of the live actors; pending ones join after the update
+    actor_ids: Vec<ActorId>,
+    pending_actors: Vec<ActorId>,
+    // Sorted by draw order
+    sprites: Vec<ComponentId>,
+    sprite_shader: Option<Shader>,
+    sprite_verts: Option<VertexArray>,
+    asteroids: Vec<Asteroid>,
+    split_rules: SplitRules,
+    collision_world: CollisionWorld,
+    world_bounds: WorldBounds,
+    // Whether world_bounds tracks the window size
+    bounds_from_window: bool,
+    ticks_count: Instant,
+    accumulator: f32,
+    interpolation_alpha: f32,
+    fixed_time_step: f32,
+    max_delta_time: f32,
+    target_frame_time: Option<Duration>,
     updating_actors: bool,
-    // ship: Option<Rc<RefCell<Ship>>>,
+    ship: Option<ActorId>,
+    random: Random,
+    recording: Option<InputRecording>,
+    replay: Option<Replay>,
 }
 
 impl Game {
     pub fn new() -> Self {
+        Game::with_config(GameConfig::default())
+    }
+
+    pub fn with_config(config: GameConfig) -> Self {
+        let video = if config.headless {
+            None
+        } else {
+            Some(Game::create_video(&config))
+        };
+
+        // A replay has to start from the same seed and world it was recorded in
+        let replay = config.replay.map(Replay::new);
+        let recorded = replay.as_ref().map(|replay| replay.get_recording());
+        let seed = recorded
+            .map(|recording| recording.get_seed())
+            .or(config.seed)
+            .unwrap_or_else(Random::generate_seed);
+        let fixed_time_step = recorded.map_or(config.fixed_time_step, |recording| recording.get_fixed_time_step());
+        let (fixed_time_step, max_delta_time) = validate_time_steps(fixed_time_step, config.max_delta_time);
+        let world_bounds = recorded.map(|recording| recording.get_world_bounds()).or(config.world_bounds);
+        let recording = config.record_input.then(|| {
+            let bounds = world_bounds.unwrap_or_else(|| {
+                WorldBounds::from_size(config.window_width as f32, config.window_height as f32)
+            });
+            InputRecording::new(seed, fixed_time_step, bounds)
+        });
+
+        let audio = Audio::new(&config.audio, video.as_ref().map(|video| &video.sdl));
+
+        let mut game = Game {
+            video,
+            is_running: true,
+            textures: HashMap::new(),
+            sounds: HashMap::new(),
+            audio,
+            input_map: config.input_map,
+            mute_held: false,
+            actors: Registry::new(),
+            components: Registry::new(),
+            actor_ids: Vec::new(),
+            pending_actors: Vec::new(),
+            sprites: Vec::new(),
+            sprite_shader: None,
+            sprite_verts: None,
+            asteroids: Vec::new(),
+            split_rules: SplitRules::default(),
+            collision_world: CollisionWorld::new(),
+            world_bounds: world_bounds.unwrap_or_else(|| {
+                WorldBounds::from_size(config.window_width as f32, config.window_height as f32)
+            }),
+            bounds_from_window: world_bounds.is_none(),
+            ticks_count: Instant::now(),
+            accumulator: 0.0,
+            interpolation_alpha: 0.0,
+            fixed_time_step,
+            max_delta_time,
+            target_frame_time: config
+                .target_fps
+                .filter(|&fps| fps > 0 && (config.headless || !config.vsync))
+                .map(|fps| Duration::from_secs_f64(1.0 / fps as f64)),
+            updating_actors: false,
+            ship: None,
+            random: Random::new(seed),
+            recording,
+            replay,
+        };
+
+        if !game.is_headless() {
+            game.load_shaders();
+            game.create_sprite_verts();
+        }
+
+        game
+    }
+
+    fn create_video(config: &GameConfig) -> Video {
         let sdl = sdl2::init().unwrap();
         let video_subsystem = sdl.video().unwrap();
 
@@ -46,7 +242,7 @@ impl Game {
 
         // Window creation
         let window = video_subsystem
-            .window("Asteroid", 1024, 768)
+            .window(&config.title, config.window_width, config.window_height)
             .opengl()
             .resizable()
             .build()
@@ -55,127 +251,1059 @@ impl Game {
         let context = window.gl_create_context().unwrap();
         gl::load_with(|s| video_subsystem.gl_get_proc_address(s) as *const _);
 
-        let canvas = window.into_canvas().build().unwrap();
 
-        Game {
-            canvas,
+        let swap_interval = if config.vsync {
+            SwapInterval::VSync
+        } else {
+            SwapInterval::Immediate
+        };
+        if let Err(err) = video_subsystem.gl_set_swap_interval(swap_interval) {
+            eprintln!("Failed to set swap interval: {}", err);
+        }
+
+        let gamepads = Gamepads::new(&sdl)
+            .map_err(|err| eprintln!("Game controllers unavailable: {}", err))
+            .ok();
+
+        Video {
+            sdl,
+            window,
             context,
-            is_running: true,
-            textures: HashMap::new(),
-            actors: Vec::new(),
-            pending_actors: Vec::new(),
-            sprites: Vec::new(),
-            // sprite_shader: None,
-            // sprite_verts: None,
-            asteroids: Vec::new(),
-            window: None,
-            ticks_count: 0,
-            updating_actors: false,
-            // ship: None,
+            gamepads,
+        }
+    }
+
+    fn load_shaders(&mut self) {
+        let mut shader = Shader::new();
+        if let Err(err) = shader.load("Shaders/Sprite.vert", "Shaders/Sprite.frag") {
+            eprintln!("{}", err);
+            return;
         }
+        shader.set_active();
+
+        // The world bounds fill the window
+        shader.set_matrix_uniform("uViewProj", &self.world_bounds.get_view_proj());
+        self.sprite_shader = Some(shader);
+    }
+
+    pub fn get_world_bounds(&self) -> &WorldBounds {
+        &self.world_bounds
+    }
+
+    /// Pin the world to `bounds`; window resizes no longer change it.
+    pub fn set_world_bounds(&mut self, bounds: WorldBounds) {
+        self.bounds_from_window = false;
+        self.world_bounds = bounds;
+        self.update_view_proj();
+    }
+
+    fn on_window_resized(&mut self, width: i32, height: i32) {
+        unsafe {
+            gl::Viewport(0, 0, width, height);
+        }
+        if self.bounds_from_window {
+            self.world_bounds = WorldBounds::from_size(width as f32, height as f32);
+            self.update_view_proj();
+        }
+    }
+
+    fn update_view_proj(&self) {
+        if let Some(shader) = &self.sprite_shader {
+            shader.set_active();
+            shader.set_matrix_uniform("uViewProj", &self.world_bounds.get_view_proj());
+        }
+    }
+
+    fn create_sprite_verts(&mut self) {
+        // Unit quad centered on the origin: x, y, z, u, v
+        let vertices: [f32; 20] = [
+            -0.5, 0.5, 0.0, 0.0, 0.0, // top left
+            0.5, 0.5, 0.0, 1.0, 0.0, // top right
+            0.5, -0.5, 0.0, 1.0, 1.0, // bottom right
+            -0.5, -0.5, 0.0, 0.0, 1.0, // bottom left
+        ];
+        let indices: [u32; 6] = [0, 1, 2, 2, 3, 0];
+
+        self.sprite_verts = Some(VertexArray::new(&vertices, &indices));
     }
 
     pub fn run_loop(&mut self) {
-        let sdl_context = sdl2::init().unwrap();
-        let mut event_pump = sdl_context.event_pump().unwrap();
+        let mut event_pump = self
+            .video
+            .as_ref()
+            .map(|video| video.sdl.event_pump().unwrap());
 
+        self.ticks_count = Instant::now();
         while self.is_running {
-            self.process_input(&mut event_pump);
-            self.update_game();
+            let input = match event_pump.as_mut() {
+                Some(event_pump) => self.process_input(event_pump),
+                None => InputState::new(),
+            };
+            let frame_time = self.measure_delta_time();
+            self.run_frame(&input, frame_time);
             self.generate_output();
+            self.wait_for_next_frame();
+        }
+    }
+
+    /// One frame of the loop: hand `input` to the actors, then simulate
+    /// `frame_time`. In replay mode the next recorded frame is used instead
+    /// of both, and the game stops once the recording runs out.
+    pub fn run_frame(&mut self, input: &InputState, frame_time: f32) {
+        self.process_game_actions(input);
+
+        let (input, recorded_steps) = match self.replay.as_mut() {
+            Some(replay) => match replay.next_frame() {
+                Some(frame) => (frame.input.clone(), Some(frame.steps)),
+                None => {
+                    self.is_running = false;
+                    return;
+                }
+            },
+            None => (input.clone(), None),
+        };
+
+        self.process_actor_input(&input);
+        let steps = match recorded_steps {
+            Some(steps) => {
+                for _ in 0..steps {
+                    self.update_game(self.fixed_time_step);
+                }
+                // The recording has no leftover time to blend with
+                self.interpolation_alpha = 1.0;
+                steps
+            }
+            None => self.advance(frame_time),
+        };
+
+        if let Some(recording) = self.recording.as_mut() {
+            recording.add_frame(input, steps);
+        }
+    }
+
+    // Game-wide actions. These read the live devices even during a replay.
+    fn process_game_actions(&mut self, input: &InputState) {
+        // Once per press, like the ship's thrust sound
+        let mute_down = self.input_map.is_action_down(Action::ToggleMute, input);
+        if mute_down && !self.mute_held {
+            self.audio.toggle_mute();
+        }
+        self.mute_held = mute_down;
+    }
+
+    /// Feed `frame_time` seconds of real time into the simulation, running
+    /// as many fixed steps as fit and keeping the remainder for next frame.
+    /// Returns the number of steps run.
+    pub fn advance(&mut self, frame_time: f32) -> u32 {
+        let mut steps = 0;
+        self.accumulator += frame_time.clamp(0.0, self.max_delta_time);
+        while self.accumulator >= self.fixed_time_step {
+            self.update_game(self.fixed_time_step);
+            self.accumulator -= self.fixed_time_step;
+            steps += 1;
+        }
+        self.interpolation_alpha = self.accumulator / self.fixed_time_step;
+        steps
+    }
+
+    pub fn get_seed(&self) -> u64 {
+        self.random.get_seed()
+    }
+
+    /// The game's random numbers. Gameplay should draw from this one only,
+    /// so a seed (or a replay) reproduces the session.
+    pub fn get_random(&self) -> &Random {
+        &self.random
+    }
+
+    pub fn get_random_mut(&mut self) -> &mut Random {
+        &mut self.random
+    }
+
+    /// Input recorded so far, if `GameConfig::record_input` was set.
+    pub fn get_recording(&self) -> Option<&InputRecording> {
+        self.recording.as_ref()
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        self.replay.as_ref().is_some_and(|replay| !replay.is_finished())
+    }
+
+    /// How far real time is between the last and the next simulation
+    /// step, in the range [0, 1]. Sprites are drawn this far from their
+    /// place before the last step to their current one.
+    pub fn get_interpolation_alpha(&self) -> f32 {
+        self.interpolation_alpha
+    }
+
+    fn measure_delta_time(&mut self) -> f32 {
+        let now = Instant::now();
+        let delta_time = now.duration_since(self.ticks_count).as_secs_f32();
+        self.ticks_count = now;
+        delta_time
+    }
+
+    fn wait_for_next_frame(&self) {
+        if let Some(target_frame_time) = self.target_frame_time {
+            let elapsed = self.ticks_count.elapsed();
+            if elapsed < target_frame_time {
+                std::thread::sleep(target_frame_time - elapsed);
+            }
         }
     }
 
+    pub fn is_headless(&self) -> bool {
+        self.video.is_none()
+    }
+
+    pub fn shutdown(&mut self) {
+        self.is_running = false;
+    }
+
     pub fn get_texture(
         &mut self,
         file_name: &str,
     ) -> Option<Rc<RefCell<Texture>>> {
         if let Some(tex) = self.textures.get(file_name) {
-            return Some(Rc::clone(tex));
+            Some(Rc::clone(tex))
         } else {
-            let mut tex = Rc::new(RefCell::new(Texture::new()));
-            let texture_creator: TextureCreator<WindowContext> = self.canvas.texture_creator();
+            // Nothing to upload to without a GL context
+            if self.is_headless() {
+                return None;
+            }
+            let mut tex = Texture::new();
 
-            if tex.borrow_mut().load(&texture_creator, file_name).is_ok() {
-                self.textures.insert(file_name.to_string(), Rc::clone(&tex));
-                return Some(tex);
-            } else {
+            if let Err(err) = tex.load(file_name) {
+                eprintln!("{}", err);
                 return None;
             }
+            Some(self.add_texture(file_name, tex))
+        }
+    }
+
+    /// Put a texture built in code (`Texture::from_rgba`, `from_bytes`) into
+    /// the cache under `name`, so later `get_texture(name)` calls find it.
+    pub fn add_texture(&mut self, name: &str, texture: Texture) -> Rc<RefCell<Texture>> {
+        let tex = Rc::new(RefCell::new(texture));
+        self.textures.insert(name.to_string(), Rc::clone(&tex));
+        tex
+    }
+
+    pub fn get_sound(&mut self, file_name: &str) -> Option<Arc<Sound>> {
+        if let Some(sound) = self.sounds.get(file_name) {
+            return sound.clone();
+        }
+        // Nobody would hear it
+        if self.audio.is_null() {
+            return None;
+        }
+        match Sound::load(file_name) {
+            Ok(sound) => Some(self.add_sound(file_name, sound)),
+            Err(err) => {
+                // Reported once; later calls find the miss in the cache
+                eprintln!("{}", err);
+                self.sounds.insert(file_name.to_string(), None);
+                None
+            }
         }
     }
 
-    fn process_input(&mut self, event_pump: &mut sdl2::EventPump) {
+    /// Put a sound built in code (`Sound::from_samples`) into the cache
+    /// under `name`, so later `get_sound(name)` calls find it.
+    pub fn add_sound(&mut self, name: &str, sound: Sound) -> Arc<Sound> {
+        let sound = Arc::new(sound);
+        self.sounds.insert(name.to_string(), Some(Arc::clone(&sound)));
+        sound
+    }
+
+    // One-shot effect; None if the sound isn't available
+    pub fn play_sound(&mut self, file_name: &str) -> Option<VoiceId> {
+        let sound = self.get_sound(file_name)?;
+        Some(self.audio.play(&sound, Channel::Effects))
+    }
+
+    // Loop as background music, replacing whatever was playing
+    pub fn play_music(&mut self, file_name: &str) -> Option<VoiceId> {
+        let sound = self.get_sound(file_name)?;
+        Some(self.audio.play_music(&sound))
+    }
+
+    pub fn get_audio(&self) -> &Audio {
+        &self.audio
+    }
+
+    pub fn get_audio_mut(&mut self) -> &mut Audio {
+        &mut self.audio
+    }
+
+    // Handle window/controller events and snapshot the devices
+    fn process_input(&mut self, event_pump: &mut sdl2::EventPump) -> InputState {
         for event in event_pump.poll_iter() {
+            // Hotplug events are the gamepads' alone
+            if self.get_gamepads_mut().is_some_and(|gamepads| gamepads.handle_event(&event)) {
+                continue;
+            }
             match event {
                 Event::Quit { .. } => self.is_running = false,
+                Event::Window {
+                    win_event: WindowEvent::SizeChanged(width, height),
+                    ..
+                } => self.on_window_resized(width, height),
                 _ => {}
             }
         }
 
-        let keyboard_state = event_pump.keyboard_state();
-        if keyboard_state.is_scancode_pressed(Scancode::Escape) {
+        let mut input = input_state_from(event_pump);
+        if let Some(gamepads) = self.get_gamepads() {
+            gamepads.apply(&mut input);
+        }
+        if input.is_key_down(Scancode::Escape) {
             self.is_running = false;
         }
+        input
+    }
+
+    /// Connected game controllers; `None` when headless.
+    pub fn get_gamepads(&self) -> Option<&Gamepads> {
+        self.video.as_ref()?.gamepads.as_ref()
     }
 
-    fn update_game(&mut self) {
-        // Game update logic goes here
+    fn get_gamepads_mut(&mut self) -> Option<&mut Gamepads> {
+        self.video.as_mut()?.gamepads.as_mut()
     }
 
-    fn generate_output(&mut self) {
+    /// Hand one frame of device state to every actor and from there to its
+    /// components, which map it to actions through their `InputMap`.
+    pub fn process_actor_input(&mut self, input: &InputState) {
+        self.updating_actors = true;
+        for id in self.actor_ids.clone() {
+            Actor::process_input(self, id, input);
+        }
+        self.updating_actors = false;
+    }
+
+    pub fn update_game(&mut self, delta_time: f32) {
+        // Find contacts first so actors can react to them this step
+        self.update_collisions();
+
+        // Update all actors. Anything created meanwhile lands in
+        // pending_actors instead of growing the list being walked.
+        self.updating_actors = true;
+        for id in self.actor_ids.clone() {
+            Actor::update(self, id, delta_time);
+        }
+        self.updating_actors = false;
+
+        // Move any pending actors to actors
+        for id in std::mem::take(&mut self.pending_actors) {
+            if let Some(actor) = self.actors.get_mut(id) {
+                actor.compute_world_transform();
+                self.actor_ids.push(id);
+            }
+        }
+
+        // Remove the dead actors
+        let dead_actors: Vec<ActorId> = self
+            .actor_ids
+            .iter()
+            .copied()
+            .filter(|&id| self.actors.get(id).is_some_and(|actor| actor.is_dead()))
+            .collect();
+        for id in dead_actors {
+            self.remove_actor(id);
+        }
+
+        // Keep the null/wave outputs in step with game time
+        self.audio.update(delta_time);
+    }
+
+    fn generate_output(&self) {
+        let Some(video) = self.video.as_ref() else {
+            return;
+        };
+
+        video.window.gl_make_current(&video.context).unwrap();
         unsafe {
             gl::ClearColor(0.86, 0.86, 0.86, 1.0);
             gl::Clear(gl::COLOR_BUFFER_BIT);
         }
-        self.canvas.present();
-        std::thread::sleep(Duration::from_millis(16)); // Cap the frame rate
+
+        // Draw all sprite components, back to front
+        if let (Some(shader), Some(verts)) = (&self.sprite_shader, &self.sprite_verts) {
+            unsafe {
+                gl::Enable(gl::BLEND);
+                gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+            }
+            shader.set_active();
+            verts.set_active();
+
+            for &sprite in &self.sprites {
+                if let Some(sprite) = self.get_component::<SpriteComponent>(sprite) {
+                    sprite.draw(self, shader, verts);
+                }
+            }
+        }
+
+        video.window.gl_swap_window();
+    }
+
+    /// Create the player's ship and the starting asteroids.
+    pub fn load_data(&mut self) {
+        self.ship = Some(Ship::new(self, ShipConfig::default()));
+
+        // Create asteroids
+        const NUM_ASTEROIDS: usize = 20;
+        for _ in 0..NUM_ASTEROIDS {
+            Asteroid::new(self);
+        }
+
+        self.play_music("Assets/Music.wav");
     }
 
-    pub fn add_actor(&mut self, actor: Rc<RefCell<Actor>>) {
+    pub fn get_ship(&self) -> Option<ActorId> {
+        self.ship.filter(|&ship| self.actors.contains(ship))
+    }
+
+    pub fn get_actor_ids(&self) -> &[ActorId] {
+        &self.actor_ids
+    }
+
+    pub fn add_actor(&mut self, actor: Actor) -> ActorId {
+        let id = self.actors.insert(actor);
         if self.updating_actors {
-            self.pending_actors.push(actor);
+            self.pending_actors.push(id);
         } else {
-            self.actors.push(actor);
+            self.actor_ids.push(id);
+        }
+        id
+    }
+
+    /// Destroy an actor along with all of its components. Stale ids are
+    /// ignored.
+    pub fn remove_actor(&mut self, id: ActorId) {
+        let Some(actor) = self.actors.remove(id) else {
+            return;
+        };
+
+        self.pending_actors.retain(|&pending| pending != id);
+        self.actor_ids.retain(|&live| live != id);
+        self.asteroids.retain(|asteroid| asteroid.get_actor() != id);
+        for component in actor.get_components() {
+            self.sprites.retain(|&sprite| sprite != component);
+            self.collision_world.remove_collider(component);
+            self.components.remove(component);
         }
     }
 
-    pub fn remove_actor(&mut self, actor: Rc<RefCell<Actor>>) {
-        if let Some(index) = self.pending_actors.iter().position(|x| Rc::ptr_eq(x, &actor)) {
-            self.pending_actors.swap_remove(index);
+    pub fn get_actor(&self, id: ActorId) -> Option<&Actor> {
+        self.actors.get(id)
+    }
+
+    pub fn get_actor_mut(&mut self, id: ActorId) -> Option<&mut Actor> {
+        self.actors.get_mut(id)
+    }
+
+    /// Register a component and attach it to its owner in update order.
+    pub fn add_component(&mut self, component: Box<dyn Component>) -> ComponentId {
+        let owner = component.get_owner();
+        let update_order = component.get_update_order();
+        let id = self.components.insert(component);
+        if let Some(actor) = self.actors.get_mut(owner) {
+            actor.add_component(id, update_order);
         }
-        if let Some(index) = self.actors.iter().position(|x| Rc::ptr_eq(x, &actor)) {
-            self.actors.swap_remove(index);
+        id
+    }
+
+    pub fn remove_component(&mut self, id: ComponentId) {
+        // A checked-out component is unhooked from its owner in with_component
+        let owner = self.components.get(id).map(|component| component.get_owner());
+        if let Some(actor) = owner.and_then(|owner| self.actors.get_mut(owner)) {
+            actor.remove_component(id);
         }
+        self.sprites.retain(|&sprite| sprite != id);
+        self.collision_world.remove_collider(id);
+        self.components.remove(id);
     }
 
-    pub fn add_asteroid(&mut self, asteroid: Rc<RefCell<Asteroid>>) {
+    pub fn get_component<T: Component>(&self, id: ComponentId) -> Option<&T> {
+        self.components
+            .get(id)
+            .and_then(|component| component.as_ref().as_any().downcast_ref())
+    }
+
+    pub fn get_component_mut<T: Component>(&mut self, id: ComponentId) -> Option<&mut T> {
+        self.components
+            .get_mut(id)
+            .and_then(|component| component.as_mut().as_any_mut().downcast_mut())
+    }
+
+    /// Run `f` on a component while it is checked out of the registry, so
+    /// it can be handed the rest of the game mutably.
+    pub fn with_component<R>(
+        &mut self,
+        id: ComponentId,
+        f: impl FnOnce(&mut dyn Component, &mut Game) -> R,
+    ) -> Option<R> {
+        let mut component = self.components.take(id)?;
+        let result = f(component.as_mut(), self);
+        if self.components.contains(id) {
+            self.components.restore(id, component);
+        } else if let Some(actor) = self.actors.get_mut(component.get_owner()) {
+            // Removed itself while running
+            actor.remove_component(id);
+        }
+        Some(result)
+    }
+
+    pub fn add_asteroid(&mut self, asteroid: Asteroid) {
         self.asteroids.push(asteroid);
     }
 
-    pub fn remove_asteroid(&mut self, asteroid: Rc<RefCell<Asteroid>>) {
-        if let Some(pos) = self.asteroids.iter().position(|a| Rc::ptr_eq(a, &asteroid)) {
-            self.asteroids.remove(pos);
+    pub fn remove_asteroid(&mut self, asteroid: Asteroid) {
+        self.asteroids.retain(|a| a.get_actor() != asteroid.get_actor());
+    }
+
+    pub fn get_asteroids(&self) -> &[Asteroid] {
+        &self.asteroids
+    }
+
+    pub fn get_collision_world(&self) -> &CollisionWorld {
+        &self.collision_world
+    }
+
+    pub fn get_collision_world_mut(&mut self) -> &mut CollisionWorld {
+        &mut self.collision_world
+    }
+
+    /// See `CollisionWorld::raycast`.
+    pub fn raycast(&self, origin: Vector2, direction: Vector2, max_distance: f32, layer_mask: u32) -> Option<RaycastHit> {
+        self.collision_world
+            .raycast(self, origin, direction, max_distance, layer_mask)
+    }
+
+    /// See `CollisionWorld::segment_query`.
+    pub fn segment_query(&self, start: Vector2, end: Vector2, layer_mask: u32) -> Option<RaycastHit> {
+        self.collision_world.segment_query(self, start, end, layer_mask)
+    }
+
+    fn update_collisions(&mut self) {
+        // Checked out like a component, since it reads the rest of the game
+        let mut collision_world = std::mem::take(&mut self.collision_world);
+        collision_world.update(self);
+        self.collision_world = collision_world;
+
+        let events = self.collision_world.get_events().to_vec();
+        resolve_contacts(self, &events);
+    }
+
+    pub fn get_split_rules(&self) -> &SplitRules {
+        &self.split_rules
+    }
+
+    // Applies to asteroids destroyed from now on, e.g. when a level starts
+    pub fn set_split_rules(&mut self, rules: SplitRules) {
+        self.split_rules = rules;
+    }
+
+    pub fn add_sprite(&mut self, sprite: ComponentId) {
+        let Some(my_draw_order) = self.sprite_draw_order(sprite) else {
+            return;
+        };
+
+        let insert_pos = self
+            .sprites
+            .iter()
+            .position(|&existing| {
+                self.sprite_draw_order(existing)
+                    .is_some_and(|draw_order| my_draw_order < draw_order)
+            })
+            .unwrap_or(self.sprites.len()); // 見つからなければ末尾に挿入
+
+        self.sprites.insert(insert_pos, sprite);
+    }
+
+    pub fn remove_sprite(&mut self, sprite: ComponentId) {
+        self.sprites.retain(|&s| s != sprite);
+    }
+
+    fn sprite_draw_order(&self, sprite: ComponentId) -> Option<i32> {
+        self.get_component::<SpriteComponent>(sprite)
+            .map(|sprite| sprite.get_draw_order())
+    }
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Game::new()
+    }
+}
+
+// Keep advance() from looping forever, never stepping at all or catching
+// up on an unbounded stall
+fn validate_time_steps(fixed_time_step: f32, max_delta_time: f32) -> (f32, f32) {
+    let defaults = GameConfig::default();
+    let fixed_time_step = if fixed_time_step.is_finite() && fixed_time_step > 0.0 {
+        fixed_time_step
+    } else {
+        eprintln!("Invalid fixed time step {}, using {}", fixed_time_step, defaults.fixed_time_step);
+        defaults.fixed_time_step
+    };
+    let max_delta_time = if max_delta_time.is_finite() {
+        max_delta_time
+    } else {
+        eprintln!("Invalid max delta time {}, using {}", max_delta_time, defaults.max_delta_time);
+        defaults.max_delta_time
+    };
+    let max_delta_time = if max_delta_time >= fixed_time_step {
+        max_delta_time
+    } else {
+        eprintln!("Max delta time {} is shorter than one step, using {}", max_delta_time, fixed_time_step);
+        fixed_time_step
+    };
+    (fixed_time_step, max_delta_time)
+}
+
+// Snapshot SDL's keyboard and mouse for this frame
+fn input_state_from(event_pump: &sdl2::EventPump) -> InputState {
+    let mut input = InputState::new();
+    for (scancode, pressed) in event_pump.keyboard_state().scancodes() {
+        input.set_key(scancode, pressed);
+    }
+    for button in event_pump.mouse_state().pressed_mouse_buttons() {
+        input.set_mouse_button(button, true);
+    }
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actor::State;
+    use crate::component::BaseComponent;
+    use crate::input_component::InputComponent;
+    use crate::input_map::{Action, Binding};
+    use crate::input_recording::InputRecording;
+    use crate::move_component::MoveComponent;
+
+    type Hook = Box<dyn FnMut(&mut Game, ActorId)>;
+
+    // Runs an arbitrary closure every time its owner updates
+    struct HookComponent {
+        base: BaseComponent,
+        on_update: Hook,
+    }
+
+    impl HookComponent {
+        fn attach(
+            game: &mut Game,
+            owner: ActorId,
+            on_update: impl FnMut(&mut Game, ActorId) + 'static,
+        ) -> ComponentId {
+            game.add_component(Box::new(HookComponent {
+                base: BaseComponent::new(owner, 100),
+                on_update: Box::new(on_update),
+            }))
         }
     }
 
-    pub fn add_sprite(&mut self, sprite: Rc<RefCell<SpriteComponent>>) {
-        let my_draw_order = sprite.borrow().get_draw_order();
-        let mut insert_pos = self.sprites.len(); // 挿入位置を末尾に設定
+    impl Component for HookComponent {
+        fn update(&mut self, game: &mut Game, _delta_time: f32) {
+            (self.on_update)(game, self.base.owner);
+        }
 
-        for (i, existing_sprite) in self.sprites.iter().enumerate() {
-            if my_draw_order < existing_sprite.borrow().get_draw_order() {
-                insert_pos = i;
-                break;
-            }
+        fn get_update_order(&self) -> i32 {
+            self.base.get_update_order()
         }
 
-        self.sprites.insert(insert_pos, sprite);
+        fn get_owner(&self) -> ActorId {
+            self.base.get_owner()
+        }
+    }
+
+    fn headless_game() -> Game {
+        Game::with_config(GameConfig::headless())
+    }
+
+    fn update_counter(game: &mut Game, actor: ActorId) -> Rc<RefCell<u32>> {
+        let count = Rc::new(RefCell::new(0));
+        let counter = Rc::clone(&count);
+        HookComponent::attach(game, actor, move |_, _| *counter.borrow_mut() += 1);
+        count
+    }
+
+    #[test]
+    fn update_game_updates_every_active_actor() {
+        let mut game = headless_game();
+        let first = Actor::new(&mut game);
+        let second = Actor::new(&mut game);
+        let first_count = update_counter(&mut game, first);
+        let second_count = update_counter(&mut game, second);
+
+        game.update_game(1.0 / 60.0);
+        game.update_game(1.0 / 60.0);
+
+        assert_eq!(*first_count.borrow(), 2);
+        assert_eq!(*second_count.borrow(), 2);
+        assert!(!game.updating_actors);
+    }
+
+    #[test]
+    fn paused_actor_is_kept_but_not_updated() {
+        let mut game = headless_game();
+        let actor = Actor::new(&mut game);
+        let count = update_counter(&mut game, actor);
+        game.get_actor_mut(actor).unwrap().set_state(State::Paused);
+
+        game.update_game(1.0 / 60.0);
+
+        assert_eq!(*count.borrow(), 0);
+        assert_eq!(game.actor_ids, vec![actor]);
+    }
+
+    #[test]
+    fn actor_added_mid_update_waits_in_pending() {
+        let mut game = headless_game();
+        let spawner = Actor::new(&mut game);
+        let spawned = Rc::new(RefCell::new(None));
+
+        let hook_spawned = Rc::clone(&spawned);
+        HookComponent::attach(&mut game, spawner, move |game, _| {
+            if hook_spawned.borrow().is_none() {
+                let actor = Actor::new(game);
+                *hook_spawned.borrow_mut() = Some(actor);
+                assert_eq!(game.pending_actors, vec![actor]);
+                assert_eq!(game.actor_ids.len(), 1);
+            }
+        });
+
+        game.update_game(1.0 / 60.0);
+
+        let spawned = spawned.borrow().unwrap();
+        assert!(game.pending_actors.is_empty());
+        assert_eq!(game.actor_ids, vec![spawner, spawned]);
+    }
+
+    #[test]
+    fn actor_added_mid_update_is_not_updated_until_next_frame() {
+        let mut game = headless_game();
+        let spawner = Actor::new(&mut game);
+        let spawned_count = Rc::new(RefCell::new(None));
+
+        let hook_count = Rc::clone(&spawned_count);
+        HookComponent::attach(&mut game, spawner, move |game, _| {
+            if hook_count.borrow().is_none() {
+                let actor = Actor::new(game);
+                *hook_count.borrow_mut() = Some(update_counter(game, actor));
+            }
+        });
+
+        game.update_game(1.0 / 60.0);
+        let count = spawned_count.borrow().clone().unwrap();
+        assert_eq!(*count.borrow(), 0);
+
+        game.update_game(1.0 / 60.0);
+        assert_eq!(*count.borrow(), 1);
+    }
+
+    #[test]
+    fn dead_actors_are_removed_with_their_components() {
+        let mut game = headless_game();
+        let alive = Actor::new(&mut game);
+        let dead = Actor::new(&mut game);
+        let sprite = SpriteComponent::new(&mut game, dead, 100);
+        game.get_actor_mut(dead).unwrap().set_state(State::Dead);
+
+        game.update_game(1.0 / 60.0);
+
+        assert_eq!(game.actor_ids, vec![alive]);
+        assert!(game.get_actor(dead).is_none());
+        assert!(game.get_component::<SpriteComponent>(sprite).is_none());
+        assert!(game.sprites.is_empty());
+        assert!(game.components.is_empty());
+    }
+
+    #[test]
+    fn actor_killing_itself_mid_update_is_removed() {
+        let mut game = headless_game();
+        let actor = Actor::new(&mut game);
+        HookComponent::attach(&mut game, actor, |game, owner| {
+            game.get_actor_mut(owner).unwrap().set_state(State::Dead);
+        });
+
+        game.update_game(1.0 / 60.0);
+
+        assert!(game.actor_ids.is_empty());
+        assert!(game.actors.is_empty());
+        assert!(game.components.is_empty());
+    }
+
+    #[test]
+    fn actor_killed_mid_update_skips_its_own_update() {
+        let mut game = headless_game();
+        let killer = Actor::new(&mut game);
+        let victim = Actor::new(&mut game);
+        let victim_count = update_counter(&mut game, victim);
+
+        HookComponent::attach(&mut game, killer, move |game, _| {
+            game.get_actor_mut(victim).unwrap().set_state(State::Dead);
+        });
+
+        game.update_game(1.0 / 60.0);
+
+        assert_eq!(*victim_count.borrow(), 0);
+        assert_eq!(game.actor_ids, vec![killer]);
+    }
+
+    #[test]
+    fn actor_removed_mid_update_is_skipped() {
+        let mut game = headless_game();
+        let remover = Actor::new(&mut game);
+        let removed = Actor::new(&mut game);
+        let removed_count = update_counter(&mut game, removed);
+
+        HookComponent::attach(&mut game, remover, move |game, _| game.remove_actor(removed));
+
+        game.update_game(1.0 / 60.0);
+
+        assert_eq!(*removed_count.borrow(), 0);
+        assert_eq!(game.actor_ids, vec![remover]);
+        assert!(game.get_actor(removed).is_none());
+    }
+
+    #[test]
+    fn component_removing_itself_is_detached_from_its_owner() {
+        let mut game = headless_game();
+        let actor = Actor::new(&mut game);
+        let self_id = Rc::new(RefCell::new(None));
+        let hook_id = Rc::clone(&self_id);
+        let id = HookComponent::attach(&mut game, actor, move |game, _| {
+            game.remove_component(hook_id.borrow().unwrap());
+        });
+        *self_id.borrow_mut() = Some(id);
+
+        game.update_game(1.0 / 60.0);
+
+        assert!(game.components.is_empty());
+        assert!(game.get_actor(actor).unwrap().get_components().is_empty());
+    }
+
+    #[test]
+    fn actor_spawned_dead_mid_update_never_joins() {
+        let mut game = headless_game();
+        let spawner = Actor::new(&mut game);
+        let spawned = Rc::new(RefCell::new(false));
+
+        let hook_spawned = Rc::clone(&spawned);
+        HookComponent::attach(&mut game, spawner, move |game, _| {
+            if !*hook_spawned.borrow() {
+                *hook_spawned.borrow_mut() = true;
+                let actor = Actor::new(game);
+                game.get_actor_mut(actor).unwrap().set_state(State::Dead);
+            }
+        });
+
+        game.update_game(1.0 / 60.0);
+
+        assert_eq!(game.actor_ids, vec![spawner]);
+        assert!(game.pending_actors.is_empty());
+        assert_eq!(game.actors.len(), 1);
     }
 
-    pub fn remove_sprite(&mut self, sprite: Rc<RefCell<SpriteComponent>>) {
-        if let Some(pos) = self.sprites.iter().position(|s| Rc::ptr_eq(s, &sprite)) {
-            self.sprites.remove(pos);
+    #[test]
+    fn input_component_steers_its_owner() {
+        let mut game = headless_game();
+        let ship = Actor::new(&mut game);
+        let input = InputComponent::new(&mut game, ship, 10);
+        let input_component = game.get_component_mut::<InputComponent>(input).unwrap();
+        input_component.set_max_forward_speed(300.0);
+        input_component.get_input_map_mut().bind(Action::Thrust, Binding::Key(Scancode::W));
+        input_component.get_input_map_mut().bind(Action::RotateLeft, Binding::Key(Scancode::D));
+        input_component.set_max_angular_speed(2.0);
+
+        let mut input = InputState::new();
+        input.set_key(Scancode::W, true);
+        game.process_actor_input(&input);
+        game.update_game(0.1);
+
+        let actor = game.get_actor(ship).unwrap();
+        assert!((actor.get_position().x - 30.0).abs() < 1e-3);
+        assert_eq!(actor.get_rotation(), 0.0);
+
+        input.set_key(Scancode::W, false);
+        input.set_key(Scancode::D, true);
+        game.process_actor_input(&input);
+        game.update_game(0.1);
+
+        let actor = game.get_actor(ship).unwrap();
+        assert!((actor.get_position().x - 30.0).abs() < 1e-3);
+        assert!((actor.get_rotation() - 0.2).abs() < 1e-3);
+    }
+
+    #[test]
+    fn sprites_stay_sorted_by_draw_order() {
+        let mut game = headless_game();
+        let actor = Actor::new(&mut game);
+        let middle = SpriteComponent::new(&mut game, actor, 100);
+        let back = SpriteComponent::new(&mut game, actor, 10);
+        let front = SpriteComponent::new(&mut game, actor, 150);
+
+        assert_eq!(game.sprites, vec![back, middle, front]);
+
+        game.remove_component(middle);
+        assert_eq!(game.sprites, vec![back, front]);
+        assert_eq!(game.get_actor(actor).unwrap().get_components(), vec![back, front]);
+    }
+
+    #[test]
+    fn advance_runs_whole_fixed_steps_and_keeps_the_remainder() {
+        let mut game = headless_game();
+        let actor = Actor::new(&mut game);
+        let count = update_counter(&mut game, actor);
+
+        game.advance(2.5 / 60.0);
+
+        assert_eq!(*count.borrow(), 2);
+        assert!((game.get_interpolation_alpha() - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn sprites_are_drawn_between_the_last_two_steps() {
+        let mut game = headless_game();
+        let actor = Actor::new(&mut game);
+        let move_component = MoveComponent::new(&mut game, actor, 10);
+        // One unit per step
+        game.get_component_mut::<MoveComponent>(move_component).unwrap().set_forward_speed(60.0);
+        let drawn_x = |game: &Game| {
+            let alpha = game.get_interpolation_alpha();
+            game.get_actor(actor).unwrap().get_interpolated_world_transform(alpha).get_translation().x
+        };
+
+        // Not stepped yet: drawn where it is
+        assert_eq!(drawn_x(&game), 0.0);
+
+        game.advance(1.5 / 60.0);
+        assert!((drawn_x(&game) - 0.5).abs() < 1e-3);
+        game.advance(0.25 / 60.0);
+        assert!((drawn_x(&game) - 0.75).abs() < 1e-3);
+
+        // Wrapping jumps straight to the other side
+        game.get_actor_mut(actor).unwrap().set_position(Vector2::new(511.5, 0.0));
+        game.advance(1.0 / 60.0);
+        assert!((drawn_x(&game) - -511.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn bad_time_steps_are_replaced() {
+        let mut game = Game::with_config(GameConfig {
+            fixed_time_step: 0.0,
+            max_delta_time: 0.001,
+            ..GameConfig::headless()
+        });
+        let actor = Actor::new(&mut game);
+        let count = update_counter(&mut game, actor);
+
+        // Falls back to 1/60 and lets at least one whole step through
+        assert_eq!(game.advance(1.0), 1);
+        assert_eq!(*count.borrow(), 1);
+
+        assert_eq!(validate_time_steps(f32::NAN, 0.05), (1.0 / 60.0, 0.05));
+        assert_eq!(validate_time_steps(-1.0, f32::NAN), (1.0 / 60.0, 0.05));
+        assert_eq!(validate_time_steps(1.0 / 60.0, f32::INFINITY), (1.0 / 60.0, 0.05));
+        assert_eq!(validate_time_steps(0.1, f32::INFINITY), (0.1, 0.1));
+    }
+
+    #[test]
+    fn toggle_mute_action_flips_once_per_press() {
+        let mut game = headless_game();
+        let muted = |game: &Game| game.get_audio().with_mixer(|mixer| mixer.is_muted());
+        let mut input = InputState::new();
+        input.set_gamepad_button(sdl2::controller::Button::Back, true);
+
+        game.run_frame(&input, 0.0);
+        assert!(muted(&game));
+        // Still held
+        game.run_frame(&input, 0.0);
+        assert!(muted(&game));
+
+        game.run_frame(&InputState::new(), 0.0);
+        input.set_gamepad_button(sdl2::controller::Button::Back, false);
+        input.set_key(Scancode::M, true);
+        game.run_frame(&input, 0.0);
+        assert!(!muted(&game));
+    }
+
+    #[test]
+    fn missing_sounds_are_only_looked_up_once() {
+        let path = std::env::temp_dir().join(format!("asteroid-game-audio-{}.wav", std::process::id()));
+        let mut game = Game::with_config(GameConfig {
+            audio: AudioOutput::WaveFile(path.clone()),
+            ..GameConfig::headless()
+        });
+
+        assert!(game.play_sound("Assets/DoesNotExist.wav").is_none());
+        assert!(game.sounds.get("Assets/DoesNotExist.wav").unwrap().is_none());
+        assert!(game.play_sound("Assets/DoesNotExist.wav").is_none());
+
+        // A sound added later under the same name replaces the miss
+        game.add_sound("Assets/DoesNotExist.wav", Sound::from_samples(&[0.5; 10], 1, 44100));
+        assert!(game.play_sound("Assets/DoesNotExist.wav").is_some());
+        drop(game);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    // Position and rotation of every actor, in update order
+    fn snapshot(game: &Game) -> Vec<(Vector2, f32)> {
+        game.get_actor_ids()
+            .iter()
+            .filter_map(|&id| game.get_actor(id))
+            .map(|actor| (actor.get_position(), actor.get_rotation()))
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_spawns_the_same_asteroids() {
+        let seeded = |seed| {
+            let mut game = Game::with_config(GameConfig {
+                seed: Some(seed),
+                ..GameConfig::headless()
+            });
+            game.load_data();
+            snapshot(&game)
+        };
+        assert_eq!(seeded(11), seeded(11));
+        assert_ne!(seeded(11), seeded(12));
+    }
+
+    #[test]
+    fn replay_reproduces_a_recorded_session() {
+        let mut game = Game::with_config(GameConfig {
+            seed: Some(7),
+            record_input: true,
+            ..GameConfig::headless()
+        });
+        game.load_data();
+        for frame in 0..180 {
+            let mut input = InputState::new();
+            input.set_key(Scancode::W, frame < 60);
+            input.set_key(Scancode::A, (40..90).contains(&frame));
+            input.set_key(Scancode::Space, frame % 20 == 0);
+            // Uneven frame times, like a real loop
+            game.run_frame(&input, [0.011, 0.017, 0.025][frame % 3]);
+        }
+        let expected = snapshot(&game);
+        let text = game.get_recording().unwrap().to_text();
+
+        let mut replayed = Game::with_config(GameConfig {
+            replay: Some(InputRecording::parse(&text).unwrap()),
+            ..GameConfig::headless()
+        });
+        replayed.load_data();
+        assert!(replayed.is_replaying());
+        while replayed.is_running {
+            // Live input and frame time are ignored while replaying
+            replayed.run_frame(&InputState::new(), 1.0);
         }
+        assert_eq!(replayed.get_seed(), 7);
+        assert_eq!(snapshot(&replayed), expected);
     }
 }
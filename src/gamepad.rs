@@ -0,0 +1,144 @@
+use sdl2::controller::{Axis, Button, GameController};
+use sdl2::event::Event;
+use sdl2::{GameControllerSubsystem, Sdl};
+
+use crate::input_map::InputState;
+
+pub const AXES: [Axis; 6] = [
+    Axis::LeftX,
+    Axis::LeftY,
+    Axis::RightX,
+    Axis::RightY,
+    Axis::TriggerLeft,
+    Axis::TriggerRight,
+];
+
+pub const BUTTONS: [Button; 15] = [
+    Button::A,
+    Button::B,
+    Button::X,
+    Button::Y,
+    Button::Back,
+    Button::Guide,
+    Button::Start,
+    Button::LeftStick,
+    Button::RightStick,
+    Button::LeftShoulder,
+    Button::RightShoulder,
+    Button::DPadUp,
+    Button::DPadDown,
+    Button::DPadLeft,
+    Button::DPadRight,
+];
+
+/// Every connected SDL game controller. Controllers come and go through
+/// the hotplug events handed to `handle_event`; SDL also sends an "added"
+/// event for each one already plugged in at startup.
+pub struct Gamepads {
+    subsystem: GameControllerSubsystem,
+    controllers: Vec<GameController>,
+}
+
+impl Gamepads {
+    pub fn new(sdl: &Sdl) -> Result<Self, String> {
+        Ok(Gamepads {
+            subsystem: sdl.game_controller()?,
+            controllers: Vec::new(),
+        })
+    }
+
+    // Open/close controllers on hotplug. Returns true if the event was one of ours.
+    pub fn handle_event(&mut self, event: &Event) -> bool {
+        match *event {
+            Event::ControllerDeviceAdded { which, .. } => {
+                match self.subsystem.open(which) {
+                    Ok(controller) => {
+                        // A controller can be reported twice at startup
+                        let id = controller.instance_id();
+                        if self.controllers.iter().all(|existing| existing.instance_id() != id) {
+                            self.controllers.push(controller);
+                        }
+                    }
+                    Err(err) => eprintln!("Failed to open game controller {}: {}", which, err),
+                }
+                true
+            }
+            Event::ControllerDeviceRemoved { which, .. } => {
+                self.controllers.retain(|controller| controller.instance_id() != which);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Add every controller's sticks, triggers and buttons to `input`.
+    pub fn apply(&self, input: &mut InputState) {
+        for controller in &self.controllers {
+            let axes: Vec<(Axis, i16)> = AXES.iter().map(|&axis| (axis, controller.axis(axis))).collect();
+            let pressed: Vec<Button> = BUTTONS.iter().copied().filter(|&button| controller.button(button)).collect();
+            apply_controller(input, &axes, &pressed);
+        }
+    }
+}
+
+/// Merge one controller's raw readings into `input`. With several
+/// controllers, each axis keeps whichever reading is pushed furthest and a
+/// button counts as down if any controller holds it. Deadzones are left to
+/// `InputMap`.
+pub fn apply_controller(input: &mut InputState, axes: &[(Axis, i16)], pressed: &[Button]) {
+    for &(axis, raw) in axes {
+        let value = normalize_axis(raw);
+        if value.abs() > input.get_gamepad_axis(axis).abs() {
+            input.set_gamepad_axis(axis, value);
+        }
+    }
+    for &button in pressed {
+        input.set_gamepad_button(button, true);
+    }
+}
+
+// SDL axes run -32768..32767 (triggers 0..32767)
+fn normalize_axis(raw: i16) -> f32 {
+    (raw as f32 / i16::MAX as f32).clamp(-1.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input_map::{Action, InputMap};
+
+    #[test]
+    fn raw_axes_are_normalized() {
+        assert_eq!(normalize_axis(i16::MAX), 1.0);
+        assert_eq!(normalize_axis(i16::MIN), -1.0);
+        assert_eq!(normalize_axis(0), 0.0);
+    }
+
+    #[test]
+    fn controllers_merge_into_one_input_state() {
+        let mut input = InputState::new();
+        apply_controller(&mut input, &[(Axis::LeftX, 8000), (Axis::LeftY, -32767)], &[]);
+        apply_controller(&mut input, &[(Axis::LeftX, -24000), (Axis::LeftY, 0)], &[Button::A]);
+
+        assert!((input.get_gamepad_axis(Axis::LeftX) + 24000.0 / 32767.0).abs() < 1e-6);
+        assert_eq!(input.get_gamepad_axis(Axis::LeftY), -1.0);
+        assert!(input.is_gamepad_button_down(Button::A));
+
+        // Default bindings: stick up thrusts, stick left turns left, A fires
+        let map = InputMap::default();
+        assert_eq!(map.get_axis(Action::Reverse, Action::Thrust, &input), 1.0);
+        assert!(map.get_axis(Action::RotateRight, Action::RotateLeft, &input) > 0.0);
+        assert!(map.is_action_down(Action::Fire, &input));
+    }
+
+    #[test]
+    fn resting_sticks_stay_inside_the_deadzone() {
+        let mut input = InputState::new();
+        // Typical drift of a worn stick
+        apply_controller(&mut input, &[(Axis::LeftX, 3000), (Axis::LeftY, -2500)], &[]);
+
+        let map = InputMap::default();
+        assert_eq!(map.get_axis(Action::RotateRight, Action::RotateLeft, &input), 0.0);
+        assert_eq!(map.get_axis(Action::Reverse, Action::Thrust, &input), 0.0);
+    }
+}
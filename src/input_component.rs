@@ -1,30 +1,38 @@
-use std::rc::Rc;
-use std::cell::RefCell;
-use crate::actor::Actor;
-use crate::component::{Component};
-use crate::move_component::MoveComponent;
+use crate::actor::ActorId;
+use crate::component::{Component, ComponentId};
+use crate::game::Game;
+use crate::input_map::{Action, InputMap, InputState};
+use crate::math::Vector2;
+use crate::move_component::{EdgePolicy, MoveComponent};
 
 pub struct InputComponent {
     move_component: MoveComponent,
     max_forward_speed: f32,
     max_angular_speed: f32,
-    forward_key: u8,
-    back_key: u8,
-    clockwise_key: u8,
-    counter_clockwise_key: u8,
+    input_map: InputMap,
+    enabled: bool,
+    // Physics mode: keys push with these instead of setting speeds
+    forward_thrust: f32,
+    angular_thrust: f32,
+    // Action axes from the last process_input, -1..1
+    thrust_input: f32,
+    turn_input: f32,
 }
 
 impl InputComponent {
-    pub fn new(owner: Rc<RefCell<Actor>>, update_order: i32) -> Self {
-        InputComponent {
-            move_component: MoveComponent::new(owner, update_order),
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(game: &mut Game, owner: ActorId, update_order: i32) -> ComponentId {
+        game.add_component(Box::new(InputComponent {
+            move_component: MoveComponent::unregistered(owner, update_order),
             max_forward_speed: 0.0,
             max_angular_speed: 0.0,
-            forward_key: 0,
-            back_key: 0,
-            clockwise_key: 0,
-            counter_clockwise_key: 0,
-        }
+            input_map: InputMap::new(),
+            enabled: true,
+            forward_thrust: 0.0,
+            angular_thrust: 0.0,
+            thrust_input: 0.0,
+            turn_input: 0.0,
+        }))
     }
 
     pub fn get_max_forward_speed(&self) -> f32 {
@@ -35,79 +43,119 @@ impl InputComponent {
         self.max_angular_speed
     }
 
-    pub fn get_forward_key(&self) -> u8 {
-        self.forward_key
+    pub fn set_max_forward_speed(&mut self, speed: f32) {
+        self.max_forward_speed = speed;
     }
 
-    pub fn get_back_key(&self) -> u8 {
-        self.back_key
+    pub fn set_max_angular_speed(&mut self, speed: f32) {
+        self.max_angular_speed = speed;
     }
 
-    pub fn get_clockwise_key(&self) -> u8 {
-        self.clockwise_key
+    // Thrust/Reverse and RotateLeft/RotateRight drive the owner; rebind
+    // through get_input_map_mut
+    pub fn get_input_map(&self) -> &InputMap {
+        &self.input_map
     }
 
-    pub fn get_counter_clockwise_key(&self) -> u8 {
-        self.counter_clockwise_key
+    pub fn get_input_map_mut(&mut self) -> &mut InputMap {
+        &mut self.input_map
     }
 
-    pub fn set_max_forward_speed(&mut self, speed: f32) {
-        self.max_forward_speed = speed;
+    pub fn set_input_map(&mut self, input_map: InputMap) {
+        self.input_map = input_map;
     }
 
-    pub fn set_max_angular_speed(&mut self, speed: f32) {
-        self.max_angular_speed = speed;
+    pub fn is_action_down(&self, action: Action, input: &InputState) -> bool {
+        self.enabled && self.input_map.is_action_down(action, input)
     }
 
-    pub fn set_forward_key(&mut self, key: u8) {
-        self.forward_key = key;
+    // A disabled component ignores input and leaves its owner still
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.move_component.stop();
+            self.thrust_input = 0.0;
+            self.turn_input = 0.0;
+        }
     }
 
-    pub fn set_back_key(&mut self, key: u8) {
-        self.back_key = key;
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
     }
 
-    pub fn set_clockwise_key(&mut self, key: u8) {
-        self.clockwise_key = key;
+    pub fn get_forward_thrust(&self) -> f32 {
+        self.forward_thrust
     }
 
-    pub fn set_counter_clockwise_key(&mut self, key: u8) {
-        self.counter_clockwise_key = key;
+    pub fn get_angular_thrust(&self) -> f32 {
+        self.angular_thrust
     }
-}
 
-impl Component for InputComponent {
-    fn new(owner: Rc<RefCell<Actor>>, update_order: i32) -> Self {
-        InputComponent::new(owner, update_order)
+    pub fn set_forward_thrust(&mut self, thrust: f32) {
+        self.forward_thrust = thrust;
     }
 
-    fn update(&mut self, delta_time: f32) {
-        self.move_component.update(delta_time);
+    pub fn set_angular_thrust(&mut self, thrust: f32) {
+        self.angular_thrust = thrust;
     }
 
-    fn process_input(&mut self, key_state: &[u8]) {
-        // Calculate forward speed for MoveComponent
-        let mut forward_speed = 0.0;
-        if key_state[self.forward_key as usize] != 0 {
-            forward_speed += self.max_forward_speed;
-        }
-        if key_state[self.back_key as usize] != 0 {
-            forward_speed -= self.max_forward_speed;
+    // Mass, drag, physics mode etc. live on the embedded MoveComponent
+    pub fn get_move_component(&self) -> &MoveComponent {
+        &self.move_component
+    }
+
+    pub fn get_move_component_mut(&mut self) -> &mut MoveComponent {
+        &mut self.move_component
+    }
+
+    pub fn get_edge_policy(&self) -> EdgePolicy {
+        self.move_component.get_edge_policy()
+    }
+
+    pub fn set_edge_policy(&mut self, policy: EdgePolicy) {
+        self.move_component.set_edge_policy(policy);
+    }
+}
+
+impl Component for InputComponent {
+    fn update(&mut self, game: &mut Game, delta_time: f32) {
+        // Thrust is re-applied every step, since forces only last one update
+        if self.move_component.is_physics_enabled() {
+            let forward = game
+                .get_actor(self.get_owner())
+                .map_or(Vector2::zero(), |owner| owner.get_forward());
+            self.move_component
+                .add_force(forward * (self.thrust_input * self.forward_thrust));
+            self.move_component
+                .add_torque(self.turn_input * self.angular_thrust);
         }
-        self.move_component.set_forward_speed(forward_speed);
+        self.move_component.update(game, delta_time);
+    }
 
-        // Calculate angular speed for MoveComponent
-        let mut angular_speed = 0.0;
-        if key_state[self.clockwise_key as usize] != 0 {
-            angular_speed += self.max_angular_speed;
+    fn process_input(&mut self, _game: &mut Game, input: &InputState) {
+        if !self.enabled {
+            return;
         }
-        if key_state[self.counter_clockwise_key as usize] != 0 {
-            angular_speed -= self.max_angular_speed;
+
+        // Forward/back and turning directions, partial for analog sticks
+        let thrust = self.input_map.get_axis(Action::Reverse, Action::Thrust, input);
+        let turn = self.input_map.get_axis(Action::RotateRight, Action::RotateLeft, input);
+
+        if self.move_component.is_physics_enabled() {
+            self.thrust_input = thrust;
+            self.turn_input = turn;
+        } else {
+            // Calculate speeds for MoveComponent
+            self.move_component.set_forward_speed(thrust * self.max_forward_speed);
+            self.move_component.set_angular_speed(turn * self.max_angular_speed);
         }
-        self.move_component.set_angular_speed(angular_speed);
     }
 
     fn get_update_order(&self) -> i32 {
         self.move_component.get_update_order()
     }
+
+    fn get_owner(&self) -> ActorId {
+        self.move_component.get_owner()
+    }
 }
@@ -0,0 +1,405 @@
+use std::collections::{HashMap, HashSet};
+
+use sdl2::controller::{Axis, Button};
+use sdl2::keyboard::Scancode;
+use sdl2::mouse::MouseButton;
+
+use crate::game::NUM_SCANCODES;
+
+/// Things the player can do, independent of which device does them.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    Thrust,
+    Reverse,
+    RotateLeft,
+    RotateRight,
+    Fire,
+    ToggleMute,
+}
+
+impl Action {
+    pub const ALL: [Action; 6] = [
+        Action::Thrust,
+        Action::Reverse,
+        Action::RotateLeft,
+        Action::RotateRight,
+        Action::Fire,
+        Action::ToggleMute,
+    ];
+}
+
+/// One physical input an action can be bound to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Binding {
+    Key(Scancode),
+    MouseButton(MouseButton),
+    GamepadButton(Button),
+    /// One half of a stick or a trigger. `positive` picks the direction
+    /// (SDL's stick Y axes point down).
+    GamepadAxis { axis: Axis, positive: bool },
+}
+
+/// Snapshot of every device for one frame, handed down through
+/// `Actor::process_input` to the components.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InputState {
+    // One byte per scancode, non-zero when held
+    keys: Vec<u8>,
+    mouse_buttons: HashSet<MouseButton>,
+    gamepad_buttons: HashSet<Button>,
+    // -1..1 for sticks, 0..1 for triggers
+    gamepad_axes: HashMap<Axis, f32>,
+}
+
+impl InputState {
+    pub fn new() -> Self {
+        InputState {
+            keys: vec![0; NUM_SCANCODES],
+            mouse_buttons: HashSet::new(),
+            gamepad_buttons: HashSet::new(),
+            gamepad_axes: HashMap::new(),
+        }
+    }
+
+    pub fn get_key_state(&self) -> &[u8] {
+        &self.keys
+    }
+
+    // Raw access by scancode number, for recordings. Out of range is ignored.
+    pub fn set_key_state(&mut self, scancode: usize, down: bool) {
+        if let Some(state) = self.keys.get_mut(scancode) {
+            *state = down as u8;
+        }
+    }
+
+    pub fn is_key_down(&self, key: Scancode) -> bool {
+        self.keys.get(key as usize).is_some_and(|&state| state != 0)
+    }
+
+    pub fn set_key(&mut self, key: Scancode, down: bool) {
+        if let Some(state) = self.keys.get_mut(key as usize) {
+            *state = down as u8;
+        }
+    }
+
+    pub fn get_mouse_buttons(&self) -> Vec<MouseButton> {
+        let mut buttons: Vec<MouseButton> = self.mouse_buttons.iter().copied().collect();
+        buttons.sort_by_key(|&button| button as u8);
+        buttons
+    }
+
+    pub fn is_mouse_button_down(&self, button: MouseButton) -> bool {
+        self.mouse_buttons.contains(&button)
+    }
+
+    pub fn set_mouse_button(&mut self, button: MouseButton, down: bool) {
+        if down {
+            self.mouse_buttons.insert(button);
+        } else {
+            self.mouse_buttons.remove(&button);
+        }
+    }
+
+    pub fn get_gamepad_buttons(&self) -> Vec<Button> {
+        let mut buttons: Vec<Button> = self.gamepad_buttons.iter().copied().collect();
+        buttons.sort_by_key(|&button| button as i32);
+        buttons
+    }
+
+    pub fn is_gamepad_button_down(&self, button: Button) -> bool {
+        self.gamepad_buttons.contains(&button)
+    }
+
+    pub fn set_gamepad_button(&mut self, button: Button, down: bool) {
+        if down {
+            self.gamepad_buttons.insert(button);
+        } else {
+            self.gamepad_buttons.remove(&button);
+        }
+    }
+
+    // Axes that have been set, in SDL order
+    pub fn get_gamepad_axes(&self) -> Vec<(Axis, f32)> {
+        let mut axes: Vec<(Axis, f32)> = self.gamepad_axes.iter().map(|(&axis, &value)| (axis, value)).collect();
+        axes.sort_by_key(|&(axis, _)| axis as i32);
+        axes
+    }
+
+    pub fn get_gamepad_axis(&self, axis: Axis) -> f32 {
+        self.gamepad_axes.get(&axis).copied().unwrap_or(0.0)
+    }
+
+    pub fn set_gamepad_axis(&mut self, axis: Axis, value: f32) {
+        // Centered axes aren't stored, so equal states compare equal
+        if value == 0.0 {
+            self.gamepad_axes.remove(&axis);
+        } else {
+            self.gamepad_axes.insert(axis, value.clamp(-1.0, 1.0));
+        }
+    }
+}
+
+impl Default for InputState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Which bindings drive which action. Every action can have any number of
+/// bindings; the strongest one wins.
+#[derive(Clone, Debug)]
+pub struct InputMap {
+    bindings: HashMap<Action, Vec<Binding>>,
+    // Stick travel ignored around the center, 0..1
+    deadzone: f32,
+}
+
+impl InputMap {
+    /// Analog values at or above this count as "pressed".
+    pub const PRESS_THRESHOLD: f32 = 0.5;
+
+    /// A map with nothing bound.
+    pub fn new() -> Self {
+        InputMap {
+            bindings: HashMap::new(),
+            deadzone: 0.2,
+        }
+    }
+
+    pub fn get_bindings(&self, action: Action) -> &[Binding] {
+        self.bindings.get(&action).map_or(&[], |bindings| bindings.as_slice())
+    }
+
+    pub fn set_bindings(&mut self, action: Action, bindings: Vec<Binding>) {
+        self.bindings.insert(action, bindings);
+    }
+
+    pub fn bind(&mut self, action: Action, binding: Binding) {
+        let bindings = self.bindings.entry(action).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    pub fn unbind(&mut self, action: Action, binding: Binding) {
+        if let Some(bindings) = self.bindings.get_mut(&action) {
+            bindings.retain(|&existing| existing != binding);
+        }
+    }
+
+    // Swap one binding for another in place, keeping its position. If `new`
+    // is already bound, `old` is just dropped. Returns false if `old` wasn't bound to the action.
+    pub fn rebind(&mut self, action: Action, old: Binding, new: Binding) -> bool {
+        let Some(bindings) = self.bindings.get_mut(&action) else {
+            return false;
+        };
+        let Some(index) = bindings.iter().position(|&existing| existing == old) else {
+            return false;
+        };
+        if bindings.contains(&new) {
+            bindings.remove(index);
+        } else {
+            bindings[index] = new;
+        }
+        true
+    }
+
+    pub fn clear_bindings(&mut self, action: Action) {
+        self.bindings.remove(&action);
+    }
+
+    // Every action the binding is attached to
+    pub fn get_actions(&self, binding: Binding) -> Vec<Action> {
+        Action::ALL
+            .into_iter()
+            .filter(|&action| self.get_bindings(action).contains(&binding))
+            .collect()
+    }
+
+    pub fn get_deadzone(&self) -> f32 {
+        self.deadzone
+    }
+
+    pub fn set_deadzone(&mut self, deadzone: f32) {
+        self.deadzone = deadzone.clamp(0.0, 0.99);
+    }
+
+    /// How strongly the action is held, 0..1. Buttons are 0 or 1; axes
+    /// are rescaled so the edge of the deadzone reads as 0.
+    pub fn get_action_value(&self, action: Action, input: &InputState) -> f32 {
+        self.get_bindings(action)
+            .iter()
+            .map(|&binding| self.get_binding_value(binding, input))
+            .fold(0.0, f32::max)
+    }
+
+    pub fn is_action_down(&self, action: Action, input: &InputState) -> bool {
+        self.get_action_value(action, input) >= Self::PRESS_THRESHOLD
+    }
+
+    /// Combine two opposing actions into one axis, -1..1.
+    pub fn get_axis(&self, negative: Action, positive: Action, input: &InputState) -> f32 {
+        (self.get_action_value(positive, input) - self.get_action_value(negative, input)).clamp(-1.0, 1.0)
+    }
+
+    fn get_binding_value(&self, binding: Binding, input: &InputState) -> f32 {
+        let pressed = |down: bool| if down { 1.0 } else { 0.0 };
+        match binding {
+            Binding::Key(key) => pressed(input.is_key_down(key)),
+            Binding::MouseButton(button) => pressed(input.is_mouse_button_down(button)),
+            Binding::GamepadButton(button) => pressed(input.is_gamepad_button_down(button)),
+            Binding::GamepadAxis { axis, positive } => {
+                let value = input.get_gamepad_axis(axis);
+                let travel = if positive { value } else { -value };
+                if travel <= self.deadzone {
+                    0.0
+                } else {
+                    ((travel - self.deadzone) / (1.0 - self.deadzone)).min(1.0)
+                }
+            }
+        }
+    }
+}
+
+// WASD/arrows, space or left click to fire, left stick and A on a gamepad,
+// M or Back to mute
+impl Default for InputMap {
+    fn default() -> Self {
+        let mut map = InputMap::new();
+        map.set_bindings(
+            Action::Thrust,
+            vec![
+                Binding::Key(Scancode::W),
+                Binding::Key(Scancode::Up),
+                Binding::GamepadAxis { axis: Axis::LeftY, positive: false },
+            ],
+        );
+        map.set_bindings(
+            Action::Reverse,
+            vec![
+                Binding::Key(Scancode::S),
+                Binding::Key(Scancode::Down),
+                Binding::GamepadAxis { axis: Axis::LeftY, positive: true },
+            ],
+        );
+        map.set_bindings(
+            Action::RotateLeft,
+            vec![
+                Binding::Key(Scancode::A),
+                Binding::Key(Scancode::Left),
+                Binding::GamepadAxis { axis: Axis::LeftX, positive: false },
+            ],
+        );
+        map.set_bindings(
+            Action::RotateRight,
+            vec![
+                Binding::Key(Scancode::D),
+                Binding::Key(Scancode::Right),
+                Binding::GamepadAxis { axis: Axis::LeftX, positive: true },
+            ],
+        );
+        map.set_bindings(
+            Action::Fire,
+            vec![
+                Binding::Key(Scancode::Space),
+                Binding::MouseButton(MouseButton::Left),
+                Binding::GamepadButton(Button::A),
+                Binding::GamepadAxis { axis: Axis::TriggerRight, positive: true },
+            ],
+        );
+        map.set_bindings(
+            Action::ToggleMute,
+            vec![Binding::Key(Scancode::M), Binding::GamepadButton(Button::Back)],
+        );
+        map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn any_binding_triggers_the_action() {
+        let map = InputMap::default();
+        let mut input = InputState::new();
+        assert!(!map.is_action_down(Action::Fire, &input));
+
+        input.set_mouse_button(MouseButton::Left, true);
+        assert!(map.is_action_down(Action::Fire, &input));
+        input.set_mouse_button(MouseButton::Left, false);
+        input.set_gamepad_button(Button::A, true);
+        assert!(map.is_action_down(Action::Fire, &input));
+        input.set_gamepad_button(Button::A, false);
+        input.set_key(Scancode::Space, true);
+        assert!(map.is_action_down(Action::Fire, &input));
+    }
+
+    #[test]
+    fn scancodes_above_255_can_be_bound() {
+        let mut map = InputMap::new();
+        map.bind(Action::Fire, Binding::Key(Scancode::AudioPlay));
+        assert!(Scancode::AudioPlay as usize > 255);
+
+        let mut input = InputState::new();
+        input.set_key(Scancode::AudioPlay, true);
+        assert!(map.is_action_down(Action::Fire, &input));
+    }
+
+    #[test]
+    fn axes_respect_the_deadzone() {
+        let mut map = InputMap::default();
+        map.set_deadzone(0.2);
+        let mut input = InputState::new();
+
+        input.set_gamepad_axis(Axis::LeftX, 0.15);
+        assert_eq!(map.get_axis(Action::RotateLeft, Action::RotateRight, &input), 0.0);
+
+        input.set_gamepad_axis(Axis::LeftX, 0.6);
+        assert!((map.get_axis(Action::RotateLeft, Action::RotateRight, &input) - 0.5).abs() < 1e-5);
+        assert!(map.is_action_down(Action::RotateRight, &input));
+
+        // Stick up is negative Y in SDL
+        input.set_gamepad_axis(Axis::LeftY, -1.0);
+        assert_eq!(map.get_axis(Action::Reverse, Action::Thrust, &input), 1.0);
+
+        // A key and the stick together don't go past full
+        input.set_key(Scancode::D, true);
+        assert_eq!(map.get_axis(Action::RotateLeft, Action::RotateRight, &input), 1.0);
+
+        // A deadzone of 1 would swallow the whole stick
+        map.set_deadzone(2.0);
+        assert_eq!(map.get_deadzone(), 0.99);
+        map.set_deadzone(-1.0);
+        assert_eq!(map.get_deadzone(), 0.0);
+    }
+
+    #[test]
+    fn rebinding_replaces_the_binding() {
+        let mut map = InputMap::default();
+        assert!(map.rebind(Action::Fire, Binding::Key(Scancode::Space), Binding::Key(Scancode::LCtrl)));
+        assert!(!map.rebind(Action::Fire, Binding::Key(Scancode::Space), Binding::Key(Scancode::F)));
+        assert_eq!(map.get_bindings(Action::Fire)[0], Binding::Key(Scancode::LCtrl));
+        assert_eq!(map.get_actions(Binding::Key(Scancode::LCtrl)), vec![Action::Fire]);
+
+        map.unbind(Action::Fire, Binding::Key(Scancode::LCtrl));
+        assert!(map.get_actions(Binding::Key(Scancode::LCtrl)).is_empty());
+
+        map.bind(Action::Thrust, Binding::Key(Scancode::W));
+        assert_eq!(map.get_bindings(Action::Thrust).len(), 3);
+        map.clear_bindings(Action::Thrust);
+        assert!(map.get_bindings(Action::Thrust).is_empty());
+    }
+
+    #[test]
+    fn rebinding_onto_an_existing_binding_leaves_no_duplicate() {
+        let mut map = InputMap::default();
+        let before = map.get_bindings(Action::Fire).len();
+        // Space is first, gamepad A is two further down
+        assert!(map.rebind(Action::Fire, Binding::Key(Scancode::Space), Binding::GamepadButton(Button::A)));
+        let bindings = map.get_bindings(Action::Fire);
+        assert_eq!(bindings.len(), before - 1);
+        assert_eq!(bindings.iter().filter(|&&binding| binding == Binding::GamepadButton(Button::A)).count(), 1);
+        assert!(!bindings.contains(&Binding::Key(Scancode::Space)));
+    }
+}
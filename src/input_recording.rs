@@ -0,0 +1,278 @@
+use std::fs;
+use std::path::Path;
+
+use sdl2::mouse::MouseButton;
+
+use crate::game::NUM_SCANCODES;
+use crate::gamepad::{AXES, BUTTONS};
+use crate::input_map::InputState;
+use crate::math::Vector2;
+use crate::world_bounds::WorldBounds;
+
+const HEADER: &str = "asteroid-replay 1";
+
+/// One frame of input and how many fixed steps the game ran after it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordedFrame {
+    pub input: InputState,
+    pub steps: u32,
+}
+
+/// Everything needed to play a session back: the RNG seed, the simulation
+/// settings it ran with and the input of every frame.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InputRecording {
+    seed: u64,
+    fixed_time_step: f32,
+    world_bounds: WorldBounds,
+    frames: Vec<RecordedFrame>,
+}
+
+impl InputRecording {
+    pub fn new(seed: u64, fixed_time_step: f32, world_bounds: WorldBounds) -> Self {
+        InputRecording {
+            seed,
+            fixed_time_step,
+            world_bounds,
+            frames: Vec::new(),
+        }
+    }
+
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn get_fixed_time_step(&self) -> f32 {
+        self.fixed_time_step
+    }
+
+    pub fn get_world_bounds(&self) -> WorldBounds {
+        self.world_bounds
+    }
+
+    pub fn add_frame(&mut self, input: InputState, steps: u32) {
+        self.frames.push(RecordedFrame { input, steps });
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .map_err(|err| format!("Failed to read recording {}: {}", path.display(), err))?;
+        InputRecording::parse(&text).map_err(|err| format!("{}: {}", path.display(), err))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let path = path.as_ref();
+        fs::write(path, self.to_text())
+            .map_err(|err| format!("Failed to write recording {}: {}", path.display(), err))
+    }
+
+    // Line based text: a header, the settings, then one line per frame, e.g.
+    // "frame 2 keys 26 44 mouse 1 buttons 0 axes 0:0.5"
+    pub fn to_text(&self) -> String {
+        let min = self.world_bounds.get_min();
+        let max = self.world_bounds.get_max();
+        let mut text = format!(
+            "{}\nseed {}\ntime_step {}\nbounds {} {} {} {}\n",
+            HEADER, self.seed, self.fixed_time_step, min.x, min.y, max.x, max.y
+        );
+        for frame in &self.frames {
+            let input = &frame.input;
+            let mut line = format!("frame {}", frame.steps);
+            let keys: Vec<String> = input
+                .get_key_state()
+                .iter()
+                .enumerate()
+                .filter(|&(_, &state)| state != 0)
+                .map(|(scancode, _)| scancode.to_string())
+                .collect();
+            let mouse: Vec<String> = input.get_mouse_buttons().iter().map(|&button| (button as u8).to_string()).collect();
+            let buttons: Vec<String> = input
+                .get_gamepad_buttons()
+                .iter()
+                .filter_map(|button| BUTTONS.iter().position(|known| known == button))
+                .map(|index| index.to_string())
+                .collect();
+            let axes: Vec<String> = input
+                .get_gamepad_axes()
+                .iter()
+                .filter_map(|(axis, value)| {
+                    AXES.iter().position(|known| known == axis).map(|index| format!("{}:{}", index, value))
+                })
+                .collect();
+            for (name, values) in [("keys", keys), ("mouse", mouse), ("buttons", buttons), ("axes", axes)] {
+                if !values.is_empty() {
+                    line.push_str(&format!(" {} {}", name, values.join(" ")));
+                }
+            }
+            text.push_str(&line);
+            text.push('\n');
+        }
+        text
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+        match lines.next() {
+            Some((_, line)) if line.trim() == HEADER => {}
+            _ => return Err("not an input recording".to_string()),
+        }
+
+        let mut seed = None;
+        let mut fixed_time_step = None;
+        let mut world_bounds = None;
+        let mut frames = Vec::new();
+        for (index, line) in lines {
+            let error = |message: &str| format!("line {}: {}", index + 1, message);
+            let mut words = line.split_whitespace();
+            match words.next() {
+                Some("seed") => seed = Some(parse_number(words.next()).map_err(|err| error(&err))?),
+                Some("time_step") => fixed_time_step = Some(parse_number(words.next()).map_err(|err| error(&err))?),
+                Some("bounds") => {
+                    let mut value = || parse_number::<f32>(words.next()).map_err(|err| error(&err));
+                    let min = Vector2::new(value()?, value()?);
+                    let max = Vector2::new(value()?, value()?);
+                    world_bounds = Some(WorldBounds::new(min, max));
+                }
+                Some("frame") => frames.push(parse_frame(words).map_err(|err| error(&err))?),
+                Some(other) => return Err(error(&format!("unknown entry '{}'", other))),
+                None => {}
+            }
+        }
+
+        Ok(InputRecording {
+            seed: seed.ok_or("missing seed")?,
+            fixed_time_step: fixed_time_step.ok_or("missing time_step")?,
+            world_bounds: world_bounds.ok_or("missing bounds")?,
+            frames,
+        })
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(word: Option<&str>) -> Result<T, String> {
+    let word = word.ok_or("missing value")?;
+    word.parse().map_err(|_| format!("bad value '{}'", word))
+}
+
+fn parse_frame<'a>(mut words: impl Iterator<Item = &'a str>) -> Result<RecordedFrame, String> {
+    let steps = parse_number(words.next())?;
+    let mut input = InputState::new();
+    let mut group = "";
+    for word in words {
+        if matches!(word, "keys" | "mouse" | "buttons" | "axes") {
+            group = word;
+            continue;
+        }
+        match group {
+            "keys" => {
+                let scancode: usize = parse_number(Some(word))?;
+                if scancode >= NUM_SCANCODES {
+                    return Err(format!("bad key '{}'", word));
+                }
+                input.set_key_state(scancode, true);
+            }
+            "mouse" => input.set_mouse_button(MouseButton::from_ll(parse_number(Some(word))?), true),
+            "buttons" => {
+                let index: usize = parse_number(Some(word))?;
+                let button = BUTTONS.get(index).ok_or(format!("bad button '{}'", word))?;
+                input.set_gamepad_button(*button, true);
+            }
+            "axes" => {
+                let (index, value) = word.split_once(':').ok_or(format!("bad axis '{}'", word))?;
+                let index: usize = parse_number(Some(index))?;
+                let axis = AXES.get(index).ok_or(format!("bad axis '{}'", word))?;
+                input.set_gamepad_axis(*axis, parse_number(Some(value))?);
+            }
+            _ => return Err(format!("unexpected '{}'", word)),
+        }
+    }
+    Ok(RecordedFrame { input, steps })
+}
+
+/// Plays an `InputRecording` back frame by frame.
+pub struct Replay {
+    recording: InputRecording,
+    next_frame: usize,
+}
+
+impl Replay {
+    pub fn new(recording: InputRecording) -> Self {
+        Replay {
+            recording,
+            next_frame: 0,
+        }
+    }
+
+    pub fn get_recording(&self) -> &InputRecording {
+        &self.recording
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next_frame >= self.recording.frames.len()
+    }
+
+    pub fn next_frame(&mut self) -> Option<&RecordedFrame> {
+        let frame = self.recording.frames.get(self.next_frame)?;
+        self.next_frame += 1;
+        Some(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sdl2::controller::{Axis, Button};
+    use sdl2::keyboard::Scancode;
+
+    fn sample() -> InputRecording {
+        let mut recording = InputRecording::new(1234, 1.0 / 60.0, WorldBounds::from_size(1024.0, 768.0));
+        let mut input = InputState::new();
+        input.set_key(Scancode::W, true);
+        input.set_key(Scancode::AudioPlay, true);
+        input.set_mouse_button(MouseButton::Left, true);
+        input.set_gamepad_button(Button::A, true);
+        input.set_gamepad_axis(Axis::LeftX, -0.123_456_79);
+        input.set_gamepad_axis(Axis::TriggerRight, 1.0);
+        recording.add_frame(input, 2);
+        recording.add_frame(InputState::new(), 0);
+        recording.add_frame(InputState::new(), 1);
+        recording
+    }
+
+    #[test]
+    fn text_round_trip_is_exact() {
+        let recording = sample();
+        let text = recording.to_text();
+        assert!(text.contains("frame 0\n"));
+        assert_eq!(InputRecording::parse(&text).unwrap(), recording);
+    }
+
+    #[test]
+    fn file_round_trip() {
+        let path = std::env::temp_dir().join(format!("asteroid-replay-{}.txt", std::process::id()));
+        let recording = sample();
+        recording.save(&path).unwrap();
+        let loaded = InputRecording::load(&path);
+        let _ = fs::remove_file(&path);
+        assert_eq!(loaded.unwrap(), recording);
+    }
+
+    #[test]
+    fn malformed_recordings_are_rejected() {
+        assert!(InputRecording::parse("hello").is_err());
+        assert!(InputRecording::parse(&format!("{}\nseed 1\n", HEADER)).is_err());
+        let bad_key = format!("{}\nseed 1\ntime_step 0.1\nbounds 0 0 1 1\nframe 1 keys 99999\n", HEADER);
+        assert!(InputRecording::parse(&bad_key).unwrap_err().contains("line 5"));
+    }
+
+    #[test]
+    fn replay_hands_out_frames_in_order() {
+        let mut replay = Replay::new(sample());
+        assert_eq!(replay.next_frame().unwrap().steps, 2);
+        assert_eq!(replay.next_frame().unwrap().steps, 0);
+        assert!(!replay.is_finished());
+        assert_eq!(replay.next_frame().unwrap().steps, 1);
+        assert!(replay.is_finished());
+        assert!(replay.next_frame().is_none());
+    }
+}
@@ -0,0 +1,138 @@
+use crate::actor::{Actor, ActorBehavior, ActorId, State};
+use crate::collider_component::{ColliderComponent, ColliderShape};
+use crate::collision_world::layers;
+use crate::component::ComponentId;
+use crate::game::Game;
+use crate::math::Vector2;
+use crate::move_component::{EdgePolicy, MoveComponent};
+use crate::sprite_component::SpriteComponent;
+
+const LASER_SPEED: f32 = 800.0;
+// Longer than one step of travel, so it can't skip past a thin rock
+const LASER_LENGTH: f32 = 22.0;
+
+pub struct Laser {
+    collider: ComponentId,
+    // Seconds left before the laser dies on its own
+    death_timer: f32,
+}
+
+impl Laser {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(game: &mut Game, position: Vector2, rotation: f32, lifetime: f32) -> ActorId {
+        let actor = Actor::new(game);
+        if let Some(laser_actor) = game.get_actor_mut(actor) {
+            laser_actor.set_position(position);
+            laser_actor.set_rotation(rotation);
+        }
+
+        // Create a sprite component
+        let sprite = SpriteComponent::new(game, actor, 100);
+        if let Some(texture) = game.get_texture("Assets/Laser.png") {
+            if let Some(sprite_component) = game.get_component_mut::<SpriteComponent>(sprite) {
+                sprite_component.set_texture(texture);
+            }
+        }
+
+        // Create a move component, and set a forward speed
+        let move_component = MoveComponent::new(game, actor, 10);
+        if let Some(move_component) = game.get_component_mut::<MoveComponent>(move_component) {
+            move_component.set_forward_speed(LASER_SPEED);
+            // Lasers leaving the world are gone
+            move_component.set_edge_policy(EdgePolicy::Kill);
+        }
+
+        // Create a collider along the beam (for collision)
+        let beam = ColliderShape::Segment {
+            start: Vector2::new(-LASER_LENGTH * 0.5, 0.0),
+            end: Vector2::new(LASER_LENGTH * 0.5, 0.0),
+        };
+        let collider = ColliderComponent::new(game, actor, beam);
+        if let Some(collider_component) = game.get_component_mut::<ColliderComponent>(collider) {
+            collider_component.set_layer(layers::LASER);
+            collider_component.set_mask(layers::ASTEROID);
+        }
+
+        if let Some(laser_actor) = game.get_actor_mut(actor) {
+            laser_actor.set_behavior(Box::new(Laser {
+                collider,
+                death_timer: lifetime,
+            }));
+        }
+
+        actor
+    }
+}
+
+impl ActorBehavior for Laser {
+    fn update_actor(&mut self, game: &mut Game, id: ActorId, delta_time: f32) {
+        // If we run out of time, laser is dead
+        self.death_timer -= delta_time;
+        if self.death_timer <= 0.0 {
+            if let Some(laser_actor) = game.get_actor_mut(id) {
+                laser_actor.set_state(State::Dead);
+            }
+            return;
+        }
+
+        // Do we intersect with an asteroid?
+        let hit = game.get_collision_world().get_contacts(self.collider).find_map(|other| {
+            game.get_asteroids().iter().copied().find(|asteroid| {
+                asteroid.get_collider() == other
+                    && game
+                        .get_actor(asteroid.get_actor())
+                        .is_some_and(|asteroid_actor| !asteroid_actor.is_dead())
+            })
+        });
+
+        // The first asteroid we intersect with, set ourselves dead and break the asteroid up
+        if let Some(asteroid) = hit {
+            if let Some(laser_actor) = game.get_actor_mut(id) {
+                laser_actor.set_state(State::Dead);
+            }
+            asteroid.destroy(game);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asteroid::Asteroid;
+    use crate::game::GameConfig;
+
+    #[test]
+    fn laser_dies_after_its_lifetime() {
+        let mut game = Game::with_config(GameConfig::headless());
+        // 480 units in 0.6s: still well inside the 1024-wide world at the end,
+        // so only the lifetime can remove it
+        let laser = Laser::new(&mut game, Vector2::new(-400.0, 0.0), 0.0, 0.5);
+
+        game.update_game(0.3);
+        assert!(game.get_actor(laser).is_some());
+
+        game.update_game(0.3);
+        assert!(game.get_actor(laser).is_none());
+    }
+
+    #[test]
+    fn laser_destroys_the_asteroid_it_hits() {
+        let mut game = Game::with_config(GameConfig::headless());
+        let target = Asteroid::new(&mut game);
+        let other = Asteroid::new(&mut game);
+        if let Some(asteroid_actor) = game.get_actor_mut(target.get_actor()) {
+            asteroid_actor.set_position(Vector2::new(120.0, 100.0));
+        }
+        if let Some(asteroid_actor) = game.get_actor_mut(other.get_actor()) {
+            asteroid_actor.set_position(Vector2::new(400.0, 300.0));
+        }
+        let laser = Laser::new(&mut game, Vector2::new(100.0, 100.0), 0.0, 1.0);
+
+        game.update_game(0.01);
+        assert!(game.get_actor(laser).is_none());
+        assert!(game.get_actor(target.get_actor()).is_none());
+        assert!(game.get_actor(other.get_actor()).is_some());
+        // The other one plus the target's fragments
+        assert_eq!(game.get_asteroids().len(), 3);
+    }
+}
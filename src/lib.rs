@@ -0,0 +1,30 @@
+// The game and its engine. main.rs only reads the command line and runs it,
+// so the API here is public even where the game itself doesn't call it.
+
+pub mod game;
+pub mod actor;
+pub mod math;
+pub mod component;
+pub mod asteroid;
+pub mod audio;
+pub mod circle_component;
+pub mod texture;
+pub mod random;
+pub mod move_component;
+pub mod sprite_component;
+pub mod shader;
+pub mod input_component;
+pub mod registry;
+pub mod vertex_array;
+pub mod ship;
+pub mod laser;
+pub mod world_bounds;
+pub mod collision_world;
+pub mod collision;
+pub mod collider_component;
+pub mod collision_response;
+pub mod input_map;
+pub mod gamepad;
+pub mod input_recording;
+#[cfg(any(feature = "mint", feature = "glam"))]
+pub mod math_interop;
@@ -1,20 +1,53 @@
-mod game;
-mod actor;
-mod math;
-mod component;
-mod asteroid;
-mod circle_component;
-mod texture;
-mod random;
-mod move_component;
-mod sprite_component;
-mod shader;
-mod input_component;
+use asteroid::game::{Game, GameConfig};
+use asteroid::input_recording::InputRecording;
 
-use crate::game::Game;
+const USAGE: &str = "asteroid [--seed N] [--record FILE] [--replay FILE]";
 
 fn main() {
-    let mut game = Game::new();
+    let mut config = GameConfig::default();
+    let mut record_path = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        // Only the flags we know take a value
+        let mut value = || {
+            args.next().unwrap_or_else(|| {
+                eprintln!("{} needs a value\nusage: {}", arg, USAGE);
+                std::process::exit(2);
+            })
+        };
+        match arg.as_str() {
+            "--seed" => {
+                let seed = value();
+                match seed.parse() {
+                    Ok(seed) => config.seed = Some(seed),
+                    Err(_) => eprintln!("Invalid seed: {}", seed),
+                }
+            }
+            "--record" => {
+                config.record_input = true;
+                record_path = Some(value());
+            }
+            // A session that can't be reproduced is no use, so don't fall
+            // back to live input
+            "--replay" => match InputRecording::load(value()) {
+                Ok(recording) => config.replay = Some(recording),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                }
+            },
+            _ => eprintln!("Ignoring argument: {}\nusage: {}", arg, USAGE),
+        }
+    }
+
+    let mut game = Game::with_config(config);
+    game.load_data();
     game.run_loop();
+
+    if let (Some(path), Some(recording)) = (record_path, game.get_recording()) {
+        if let Err(err) = recording.save(path) {
+            eprintln!("{}", err);
+        }
+    }
 }
 
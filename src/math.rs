@@ -1,11 +1,15 @@
+// Math:: の関数と定数 (math::math::PI のように使う)
+#[allow(clippy::module_inception)]
 pub mod math {
     use std::f32;
 
-    pub const PI: f32 = 3.1415926535;
+    pub const PI: f32 = f32::consts::PI;
     pub const TWO_PI: f32 = PI * 2.0;
     pub const PI_OVER_2: f32 = PI / 2.0;
     pub const INFINITY: f32 = f32::INFINITY;
     pub const NEG_INFINITY: f32 = f32::NEG_INFINITY;
+    // Per-component tolerance of `Approx`
+    pub const APPROX_EPSILON: f32 = 1e-4;
 
     pub fn to_radians(degrees: f32) -> f32 {
         degrees * PI / 180.0
@@ -73,6 +77,29 @@ pub mod math {
 }
 
 
+/// Component-wise comparison within `epsilon`.
+pub trait ApproxEq: Copy {
+    fn approx_eq(&self, other: Self, epsilon: f32) -> bool;
+}
+
+impl ApproxEq for f32 {
+    fn approx_eq(&self, other: f32, epsilon: f32) -> bool {
+        (self - other).abs() <= epsilon
+    }
+}
+
+/// `==` within `math::APPROX_EPSILON` per component, e.g.
+/// `assert_eq!(Approx(a), Approx(b))`. The derived PartialEq on the math
+/// types stays exact, since a tolerant one isn't transitive.
+#[derive(Debug, Clone, Copy)]
+pub struct Approx<T>(pub T);
+
+impl<T: ApproxEq> PartialEq for Approx<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.approx_eq(other.0, math::APPROX_EPSILON)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Vector2 {
     pub x: f32,
@@ -136,8 +163,10 @@ impl Vector2 {
     // 正規化
     pub fn normalize(&mut self) {
         let length = self.length();
-        self.x /= length;
-        self.y /= length;
+        if length > 0.0 {
+            self.x /= length;
+            self.y /= length;
+        }
     }
 
     // 与えられたベクトルを正規化
@@ -162,14 +191,45 @@ impl Vector2 {
         v - 2.0 * Vector2::dot(v, n) * n
     }
 
-    // ベクトルと行列の変換 (未実装)
-    // pub fn transform(vec: Vector2, mat: Matrix3, w: f32) -> Vector2 {
-    //     // Matrix3の実装に依存します
-    // }
+    // 2Dクロス積 (z component of the 3D cross product)
+    pub fn cross(a: Vector2, b: Vector2) -> f32 {
+        a.x * b.y - a.y * b.x
+    }
+
+    // 距離
+    pub fn distance(a: Vector2, b: Vector2) -> f32 {
+        (a - b).length()
+    }
+
+    pub fn distance_sq(a: Vector2, b: Vector2) -> f32 {
+        (a - b).length_sq()
+    }
+
+    // 垂直なベクトル (rotated 90 degrees counterclockwise)
+    pub fn perpendicular(&self) -> Vector2 {
+        Vector2::new(-self.y, self.x)
+    }
+
+    // x軸からの角度 (radians, -PI..PI), matching Actor rotation
+    pub fn angle(&self) -> f32 {
+        self.y.atan2(self.x)
+    }
+
+    // 2つのベクトルの間の符号付き角度 (a から b へ)
+    pub fn angle_between(a: Vector2, b: Vector2) -> f32 {
+        Vector2::cross(a, b).atan2(Vector2::dot(a, b))
+    }
+
+    // 角度で回転
+    pub fn rotate(vec: Vector2, angle: f32) -> Vector2 {
+        let (sin, cos) = angle.sin_cos();
+        Vector2::new(vec.x * cos - vec.y * sin, vec.x * sin + vec.y * cos)
+    }
 }
 
 // ベクトル演算
-use std::ops::{Add, Sub, Mul, AddAssign, SubAssign, MulAssign};
+use std::fmt;
+use std::ops::{Add, Sub, Mul, Div, Neg, Index, IndexMut, AddAssign, SubAssign, MulAssign, DivAssign};
 
 impl Add for Vector2 {
     type Output = Self;
@@ -357,15 +417,21 @@ impl Vector3 {
         v - 2.0 * Vector3::dot(v, n) * n
     }
 
-    // 行列での変換 (未実装)
-    // pub fn transform(vec: Vector3, mat: Matrix4, w: f32) -> Vector3 {
-    //     // Matrix4の実装に依存します
-    // }
+    // 距離
+    pub fn distance(a: Vector3, b: Vector3) -> f32 {
+        (a - b).length()
+    }
+
+    pub fn distance_sq(a: Vector3, b: Vector3) -> f32 {
+        (a - b).length_sq()
+    }
 
-    // クォータニオンでの変換 (未実装)
-    // pub fn transform_quaternion(vec: Vector3, q: Quaternion) -> Vector3 {
-    //     // Quaternionの実装に依存します
-    // }
+    // クォータニオンでの変換 (q must be normalized)
+    pub fn transform_quaternion(vec: Vector3, q: Quaternion) -> Vector3 {
+        // v + 2.0 * cross(q.xyz, cross(q.xyz, v) + q.w * v)
+        let qv = Vector3::new(q.x, q.y, q.z);
+        vec + 2.0 * Vector3::cross(qv, Vector3::cross(qv, vec) + q.w * vec)
+    }
 }
 
 
@@ -494,26 +560,6 @@ impl Matrix3 {
         &self.mat[0][0] as *const f32
     }
 
-    // 行列の乗算
-    pub fn mul(self, other: Matrix3) -> Matrix3 {
-        let mut result = Matrix3::identity();
-
-        for row in 0..3 {
-            for col in 0..3 {
-                result.mat[row][col] =
-                    self.mat[row][0] * other.mat[0][col] +
-                        self.mat[row][1] * other.mat[1][col] +
-                        self.mat[row][2] * other.mat[2][col];
-            }
-        }
-
-        result
-    }
-
-    // *= 演算子の実装
-    pub fn mul_assign(&mut self, other: Matrix3) {
-        *self = self.mul(other);
-    }
 
     // スケール行列の作成
     pub fn create_scale(x_scale: f32, y_scale: f32) -> Self {
@@ -575,8 +621,153 @@ impl Matrix4 {
         Self { mat }
     }
 
+    // 逆行列 (in place). A singular matrix is left unchanged.
     pub fn invert(&mut self) {
-        // Implement inversion logic here
+        if let Some(inverse) = self.inverse() {
+            *self = inverse;
+        }
+    }
+
+    // 逆行列. None if the matrix is singular.
+    pub fn inverse(&self) -> Option<Matrix4> {
+        if self.is_affine() {
+            return self.inverse_affine();
+        }
+
+        // Gauss-Jordan elimination with partial pivoting
+        let scale = self.mat.iter().flatten().fold(0.0f32, |max, value| max.max(value.abs()));
+        let mut m = self.mat;
+        let mut inv = Matrix4::IDENTITY.mat;
+        for col in 0..4 {
+            let pivot = (col..4)
+                .max_by(|&a, &b| m[a][col].abs().total_cmp(&m[b][col].abs()))
+                .unwrap_or(col);
+            if m[pivot][col].abs() <= f32::EPSILON * scale {
+                return None;
+            }
+            m.swap(col, pivot);
+            inv.swap(col, pivot);
+
+            let scale = 1.0 / m[col][col];
+            for k in 0..4 {
+                m[col][k] *= scale;
+                inv[col][k] *= scale;
+            }
+
+            for row in 0..4 {
+                let factor = m[row][col];
+                if row == col || factor == 0.0 {
+                    continue;
+                }
+                for k in 0..4 {
+                    m[row][k] -= factor * m[col][k];
+                    inv[row][k] -= factor * inv[col][k];
+                }
+            }
+        }
+        Some(Matrix4 { mat: inv })
+    }
+
+    // 最後の列が (0, 0, 0, 1): rotation/scale plus a translation row
+    pub fn is_affine(&self) -> bool {
+        self.mat[0][3] == 0.0 && self.mat[1][3] == 0.0 && self.mat[2][3] == 0.0 && self.mat[3][3] == 1.0
+    }
+
+    // Inverse of an affine matrix: invert the 3x3 part, then the translation.
+    // None if the matrix is singular or not affine.
+    pub fn inverse_affine(&self) -> Option<Matrix4> {
+        if !self.is_affine() {
+            return None;
+        }
+        let m = &self.mat;
+        let cofactor = |r0: usize, r1: usize, c0: usize, c1: usize| m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0];
+
+        // Adjugate of the upper 3x3
+        let adj = [
+            [cofactor(1, 2, 1, 2), -cofactor(0, 2, 1, 2), cofactor(0, 1, 1, 2)],
+            [-cofactor(1, 2, 0, 2), cofactor(0, 2, 0, 2), -cofactor(0, 1, 0, 2)],
+            [cofactor(1, 2, 0, 1), -cofactor(0, 2, 0, 1), cofactor(0, 1, 0, 1)],
+        ];
+        let det = m[0][0] * adj[0][0] + m[0][1] * adj[1][0] + m[0][2] * adj[2][0];
+        // Relative to the row lengths, so small but valid scales (ortho) still invert
+        let row_scale: f32 = (0..3)
+            .map(|row| (m[row][0] * m[row][0] + m[row][1] * m[row][1] + m[row][2] * m[row][2]).sqrt())
+            .product();
+        if det.abs() <= f32::EPSILON * row_scale {
+            return None;
+        }
+        let inv_det = 1.0 / det;
+
+        let mut result = Matrix4::IDENTITY;
+        for (result_row, adj_row) in result.mat.iter_mut().zip(&adj) {
+            for (value, cofactor) in result_row.iter_mut().zip(adj_row) {
+                *value = cofactor * inv_det;
+            }
+        }
+        // Row vectors: t' = -t * inverse(upper 3x3)
+        for col in 0..3 {
+            result.mat[3][col] = -(0..3).map(|k| m[3][k] * result.mat[k][col]).sum::<f32>();
+        }
+        Some(result)
+    }
+
+    // 行列式
+    pub fn determinant(&self) -> f32 {
+        let m = &self.mat;
+        // 2x2 minors of the bottom two rows
+        let s0 = m[2][0] * m[3][1] - m[2][1] * m[3][0];
+        let s1 = m[2][0] * m[3][2] - m[2][2] * m[3][0];
+        let s2 = m[2][0] * m[3][3] - m[2][3] * m[3][0];
+        let s3 = m[2][1] * m[3][2] - m[2][2] * m[3][1];
+        let s4 = m[2][1] * m[3][3] - m[2][3] * m[3][1];
+        let s5 = m[2][2] * m[3][3] - m[2][3] * m[3][2];
+
+        m[0][0] * (m[1][1] * s5 - m[1][2] * s4 + m[1][3] * s3)
+            - m[0][1] * (m[1][0] * s5 - m[1][2] * s2 + m[1][3] * s1)
+            + m[0][2] * (m[1][0] * s4 - m[1][1] * s2 + m[1][3] * s0)
+            - m[0][3] * (m[1][0] * s3 - m[1][1] * s1 + m[1][2] * s0)
+    }
+
+    // 転置
+    pub fn transpose(&mut self) {
+        *self = Matrix4::transposed(*self);
+    }
+
+    pub fn transposed(mat: Matrix4) -> Matrix4 {
+        let mut result = mat;
+        for row in 0..4 {
+            for col in 0..4 {
+                result.mat[row][col] = mat.mat[col][row];
+            }
+        }
+        result
+    }
+
+    // 平行移動成分の取得 (row vectors: the last row)
+    pub fn get_translation(&self) -> Vector3 {
+        Vector3::new(self.mat[3][0], self.mat[3][1], self.mat[3][2])
+    }
+
+    // 各軸の長さ = スケール
+    pub fn get_scale(&self) -> Vector3 {
+        Vector3::new(
+            Vector3::new(self.mat[0][0], self.mat[0][1], self.mat[0][2]).length(),
+            Vector3::new(self.mat[1][0], self.mat[1][1], self.mat[1][2]).length(),
+            Vector3::new(self.mat[2][0], self.mat[2][1], self.mat[2][2]).length(),
+        )
+    }
+
+    // 各軸 (正規化済み)
+    pub fn get_x_axis(&self) -> Vector3 {
+        Vector3::normalized(Vector3::new(self.mat[0][0], self.mat[0][1], self.mat[0][2]))
+    }
+
+    pub fn get_y_axis(&self) -> Vector3 {
+        Vector3::normalized(Vector3::new(self.mat[1][0], self.mat[1][1], self.mat[1][2]))
+    }
+
+    pub fn get_z_axis(&self) -> Vector3 {
+        Vector3::normalized(Vector3::new(self.mat[2][0], self.mat[2][1], self.mat[2][2]))
     }
 
     pub fn create_from_quaternion(q: Quaternion) -> Matrix4 {
@@ -622,28 +813,6 @@ impl Matrix4 {
         &self.mat[0][0] as *const f32
     }
 
-    // 行列の乗算
-    pub fn mul(self, other: Matrix4) -> Matrix4 {
-        let mut result = Matrix4::identity();
-
-        for row in 0..4 {
-            for col in 0..4 {
-                result.mat[row][col] =
-                    self.mat[row][0] * other.mat[0][col] +
-                        self.mat[row][1] * other.mat[1][col] +
-                        self.mat[row][2] * other.mat[2][col] +
-                        self.mat[row][3] * other.mat[3][col];
-            }
-        }
-
-        result
-    }
-
-    // *= 演算子の実装
-    pub fn mul_assign(&mut self, other: Matrix4) {
-        *self = self.mul(other);
-    }
-
     // スケール行列の作成
     pub fn create_scale(x_scale: f32, y_scale: f32, z_scale: f32) -> Self {
         Self {
@@ -718,11 +887,6 @@ impl Matrix4 {
         }
     }
 
-    // クォータニオンからの回転行列の作成 (未実装)
-    // pub fn create_from_quaternion(q: Quaternion) -> Self {
-    //     // Quaternionの実装に依存します
-    // }
-
     // 視点行列の作成
     pub fn create_look_at(eye: Vector3, target: Vector3, up: Vector3) -> Self {
         let zaxis = Vector3::normalized(target - eye);
@@ -805,6 +969,39 @@ impl Mul for Matrix4 {
     }
 }
 
+// *= 演算子の実装
+impl MulAssign for Matrix4 {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+// 行列の乗算
+impl Mul for Matrix3 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let mut result = Matrix3::identity();
+
+        for row in 0..3 {
+            for col in 0..3 {
+                result.mat[row][col] =
+                    self.mat[row][0] * rhs.mat[0][col] +
+                        self.mat[row][1] * rhs.mat[1][col] +
+                        self.mat[row][2] * rhs.mat[2][col];
+            }
+        }
+
+        result
+    }
+}
+
+impl MulAssign for Matrix3 {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Quaternion {
     pub x: f32,
@@ -867,12 +1064,15 @@ impl Quaternion {
         self.length_sq().sqrt()
     }
 
+    // Zero length is left as is, same as the vectors
     pub fn normalize(&mut self) {
         let length = self.length();
-        self.x /= length;
-        self.y /= length;
-        self.z /= length;
-        self.w /= length;
+        if length > 0.0 {
+            self.x /= length;
+            self.y /= length;
+            self.z /= length;
+            self.w /= length;
+        }
     }
 
     // クォータニオンの正規化
@@ -930,6 +1130,11 @@ impl Quaternion {
         ret_val
     }
 
+    // ベクトルを回転
+    pub fn rotate(&self, vec: Vector3) -> Vector3 {
+        Vector3::transform_quaternion(vec, *self)
+    }
+
     // クォータニオンの連結
     pub fn concatenate(q: Quaternion, p: Quaternion) -> Quaternion {
         let qv = Vector3::new(q.x, q.y, q.z);
@@ -950,6 +1155,107 @@ impl Default for Quaternion {
     }
 }
 
+// 演算子 (Neg, Div, Index) と Display
+macro_rules! impl_component_ops {
+    ($type:ident, $name:literal, $($field:ident => $index:literal),+) => {
+        impl Neg for $type {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self { $($field: -self.$field),+ }
+            }
+        }
+
+        impl Div<f32> for $type {
+            type Output = Self;
+
+            fn div(self, scalar: f32) -> Self {
+                Self { $($field: self.$field / scalar),+ }
+            }
+        }
+
+        impl DivAssign<f32> for $type {
+            fn div_assign(&mut self, scalar: f32) {
+                $(self.$field /= scalar;)+
+            }
+        }
+
+        impl Index<usize> for $type {
+            type Output = f32;
+
+            fn index(&self, index: usize) -> &f32 {
+                match index {
+                    $($index => &self.$field,)+
+                    _ => panic!("{} index out of range: {}", $name, index),
+                }
+            }
+        }
+
+        impl IndexMut<usize> for $type {
+            fn index_mut(&mut self, index: usize) -> &mut f32 {
+                match index {
+                    $($index => &mut self.$field,)+
+                    _ => panic!("{} index out of range: {}", $name, index),
+                }
+            }
+        }
+
+        // 誤差を許容した比較. For quaternions, q and -q are the same
+        // rotation but compare unequal.
+        impl ApproxEq for $type {
+            fn approx_eq(&self, other: $type, epsilon: f32) -> bool {
+                $((self.$field - other.$field).abs() <= epsilon)&&+
+            }
+        }
+
+        // "(x, y, ...)", honoring a precision like {:.2}
+        impl fmt::Display for $type {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let values = [$(self.$field),+];
+                write!(f, "(")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    match f.precision() {
+                        Some(precision) => write!(f, "{:.*}", precision, value)?,
+                        None => write!(f, "{}", value)?,
+                    }
+                }
+                write!(f, ")")
+            }
+        }
+    };
+}
+
+impl_component_ops!(Vector2, "Vector2", x => 0, y => 1);
+impl_component_ops!(Vector3, "Vector3", x => 0, y => 1, z => 2);
+impl_component_ops!(Quaternion, "Quaternion", x => 0, y => 1, z => 2, w => 3);
+
+// 成分ごとの除算
+impl Div for Vector2 {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        Self {
+            x: self.x / other.x,
+            y: self.y / other.y,
+        }
+    }
+}
+
+impl Div for Vector3 {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        Self {
+            x: self.x / other.x,
+            y: self.y / other.y,
+            z: self.z / other.z,
+        }
+    }
+}
+
 pub mod color {
     use crate::math::Vector3;
 
@@ -964,3 +1270,406 @@ pub mod color {
     pub const LIGHT_PINK: Vector3 = Vector3 { x: 1.0, y: 0.71, z: 0.76 };
     pub const LIGHT_GREEN: Vector3 = Vector3 { x: 0.56, y: 0.93, z: 0.56 };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn assert_matrix_near(a: Matrix4, b: Matrix4) {
+        for row in 0..4 {
+            for col in 0..4 {
+                assert!(
+                    (a.mat[row][col] - b.mat[row][col]).abs() < 1e-4,
+                    "{:?} != {:?}",
+                    a,
+                    b
+                );
+            }
+        }
+    }
+
+    fn assert_vector3_near(a: Vector3, b: Vector3) {
+        assert!((a - b).length() < 1e-4, "{:?} != {:?}", a, b);
+    }
+
+    fn assert_quaternion_near(a: Quaternion, b: Quaternion) {
+        assert!(a.approx_eq(b, 1e-4), "{:?} != {:?}", a, b);
+    }
+
+    fn affine_sample() -> Matrix4 {
+        Matrix4::create_scale(2.0, 3.0, 4.0)
+            * Matrix4::create_rotation_z(0.7)
+            * Matrix4::create_rotation_x(-0.3)
+            * Matrix4::create_translation(Vector3::new(5.0, -6.0, 7.0))
+    }
+
+    #[test]
+    fn general_inverse_undoes_a_projection() {
+        let proj = Matrix4::create_perspective_fov(1.2, 1024.0, 768.0, 10.0, 1000.0)
+            * Matrix4::create_translation(Vector3::new(1.0, 2.0, 3.0));
+        assert!(!proj.is_affine());
+
+        let inverse = proj.inverse().unwrap();
+        assert_matrix_near(proj * inverse, Matrix4::IDENTITY);
+        assert_matrix_near(inverse * proj, Matrix4::IDENTITY);
+    }
+
+    #[test]
+    fn affine_fast_path_matches_the_general_inverse() {
+        let m = affine_sample();
+        let fast = m.inverse_affine().unwrap();
+        assert_matrix_near(m * fast, Matrix4::IDENTITY);
+
+        let mut non_affine = m;
+        non_affine.mat[0][3] = 1e-7;
+        assert!(non_affine.inverse_affine().is_none());
+        assert_matrix_near(non_affine.inverse().unwrap(), fast);
+    }
+
+    #[test]
+    fn singular_matrix_has_no_inverse() {
+        let mut flat = Matrix4::create_scale(1.0, 0.0, 1.0);
+        assert!(flat.inverse().is_none());
+        assert_eq!(flat.determinant(), 0.0);
+
+        flat.invert();
+        assert_eq!(flat, Matrix4::create_scale(1.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn determinant_and_transpose() {
+        assert!((Matrix4::create_scale(2.0, 3.0, 4.0).determinant() - 24.0).abs() < 1e-5);
+        assert!((Matrix4::create_rotation_y(1.1).determinant() - 1.0).abs() < 1e-5);
+
+        let m = affine_sample();
+        assert!((m.determinant() - 24.0).abs() < 1e-3);
+        assert!((Matrix4::transposed(m).determinant() - m.determinant()).abs() < 1e-3);
+
+        let mut t = m;
+        t.transpose();
+        assert_eq!(t.mat[3][0], m.mat[0][3]);
+        assert_eq!(t.mat[0][3], m.mat[3][0]);
+        t.transpose();
+        assert_eq!(t, m);
+    }
+
+    #[test]
+    fn decomposition_reads_back_scale_axes_and_translation() {
+        let m = Matrix4::create_scale(2.0, 3.0, 4.0)
+            * Matrix4::create_rotation_z(math::PI_OVER_2)
+            * Matrix4::create_translation(Vector3::new(5.0, -6.0, 7.0));
+
+        assert_vector3_near(m.get_translation(), Vector3::new(5.0, -6.0, 7.0));
+        assert_vector3_near(m.get_scale(), Vector3::new(2.0, 3.0, 4.0));
+        assert_vector3_near(m.get_x_axis(), Vector3::new(0.0, 1.0, 0.0));
+        assert_vector3_near(m.get_y_axis(), Vector3::new(-1.0, 0.0, 0.0));
+        assert_vector3_near(m.get_z_axis(), Vector3::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn inverse_view_proj_maps_screen_to_world() {
+        let view_proj = Matrix4::create_translation(Vector3::new(-100.0, -50.0, 0.0))
+            * Matrix4::create_simple_view_proj(1024.0, 768.0);
+        let inverse = view_proj.inverse().unwrap();
+
+        // Top-right corner of the screen in normalized device coordinates
+        let world = Vector3::transform_with_persp_div(Vector3::new(1.0, 1.0, 0.0), inverse, 1.0);
+        assert!((world.x - 612.0).abs() < 1e-3);
+        assert!((world.y - 434.0).abs() < 1e-3);
+
+        // Tiny determinant (~5e-8) but perfectly invertible
+        let ortho = Matrix4::create_ortho(1024.0, 768.0, 1.0, 100.0);
+        let inverse = ortho.inverse().unwrap();
+        assert_matrix_near(ortho * inverse, Matrix4::IDENTITY);
+        let world = Vector3::transform_with_persp_div(Vector3::new(1.0, -1.0, 0.0), inverse, 1.0);
+        assert!((world.x - 512.0).abs() < 1e-3);
+        assert!((world.y + 384.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn vector2_angles_and_rotation() {
+        let v = Vector2::new(3.0, 4.0);
+        assert_eq!(v.perpendicular(), Vector2::new(-4.0, 3.0));
+        assert_eq!(Vector2::cross(Vector2::UNIT_X, Vector2::UNIT_Y), 1.0);
+        assert_eq!(Vector2::distance(v, Vector2::zero()), 5.0);
+        assert!((Vector2::UNIT_Y.angle() - math::PI_OVER_2).abs() < 1e-6);
+        assert!((Vector2::angle_between(Vector2::UNIT_Y, Vector2::UNIT_X) + math::PI_OVER_2).abs() < 1e-6);
+
+        let rotated = Vector2::rotate(v, math::PI_OVER_2);
+        assert!(rotated.approx_eq(v.perpendicular(), 1e-5));
+        assert!(!rotated.approx_eq(v, 1e-5));
+    }
+
+    #[test]
+    fn quaternion_rotates_and_concatenates() {
+        let q = Quaternion::from_axis_angle(Vector3::UNIT_Z, math::PI_OVER_2);
+        assert_vector3_near(Vector3::transform_quaternion(Vector3::UNIT_X, q), Vector3::UNIT_Y);
+
+        // Same result as the equivalent rotation matrix
+        let p = Quaternion::from_axis_angle(Vector3::UNIT_X, 0.4);
+        let v = Vector3::new(1.0, -2.0, 3.0);
+        let by_matrix = Vector3::transform(
+            Vector3::transform(v, Matrix4::create_rotation_z(math::PI_OVER_2), 1.0),
+            Matrix4::create_rotation_x(0.4),
+            1.0,
+        );
+        assert_vector3_near(p.rotate(q.rotate(v)), by_matrix);
+        assert_vector3_near(Quaternion::concatenate(q, p).rotate(v), by_matrix);
+    }
+
+    #[test]
+    fn component_operators() {
+        let mut v = Vector3::new(2.0, -4.0, 6.0);
+        assert_eq!(-v, Vector3::new(-2.0, 4.0, -6.0));
+        assert_eq!(v / 2.0, Vector3::new(1.0, -2.0, 3.0));
+        assert_eq!(v / Vector3::new(2.0, 4.0, 3.0), Vector3::new(1.0, -1.0, 2.0));
+        v[1] = 8.0;
+        v /= 2.0;
+        assert_eq!((v[0], v[1], v[2]), (1.0, 4.0, 3.0));
+
+        let q = Quaternion::new(1.0, 2.0, 3.0, 4.0);
+        assert_eq!(q[3], 4.0);
+        assert_eq!(-q / 2.0, Quaternion::new(-0.5, -1.0, -1.5, -2.0));
+        assert!(Vector2::new(1.0, 2.0).approx_eq(Vector2::new(1.0005, 1.9995), 1e-3));
+    }
+
+    #[test]
+    fn approx_compares_within_the_epsilon() {
+        let turned = Vector2::rotate(Vector2::UNIT_X, math::TWO_PI);
+        assert_ne!(turned, Vector2::UNIT_X);
+        assert_eq!(Approx(turned), Approx(Vector2::UNIT_X));
+        assert_ne!(Approx(Vector2::new(1.0, 2.0)), Approx(Vector2::new(1.0, 2.001)));
+
+        let q = Quaternion::from_axis_angle(Vector3::UNIT_Z, 0.5);
+        assert_eq!(Approx(Quaternion::concatenate(q, Quaternion::identity())), Approx(q));
+        assert_ne!(Approx(q), Approx(-q));
+        assert_eq!(Approx(math::PI.sin()), Approx(0.0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Vector2 index out of range: 2")]
+    fn index_out_of_range_panics() {
+        let _ = Vector2::zero()[2];
+    }
+
+    #[test]
+    fn display_formats_components() {
+        assert_eq!(Vector2::new(1.0, -2.5).to_string(), "(1, -2.5)");
+        assert_eq!(format!("{:.2}", Vector3::new(1.0, 2.0, 3.0)), "(1.00, 2.00, 3.00)");
+        assert_eq!(Quaternion::identity().to_string(), "(0, 0, 0, 1)");
+    }
+
+    #[test]
+    fn row_vector_convention_and_memory_layout() {
+        // v * M: translation lives in the bottom row, which is what
+        // Shader::set_matrix_uniform hands to GL with transpose = TRUE
+        let m = Matrix4::create_translation(Vector3::new(5.0, 6.0, 7.0));
+        let data = unsafe { std::slice::from_raw_parts(m.as_ptr(), 16) };
+        assert_eq!(&data[12..15], &[5.0, 6.0, 7.0]);
+        assert_eq!(Vector3::transform(Vector3::new(1.0, 2.0, 3.0), m, 1.0), Vector3::new(6.0, 8.0, 10.0));
+        assert_eq!(Vector3::transform(Vector3::new(1.0, 2.0, 3.0), m, 0.0), Vector3::new(1.0, 2.0, 3.0));
+
+        // Left to right: scale first, then translate
+        let world = Matrix4::create_scale(2.0, 2.0, 2.0) * m;
+        assert_vector3_near(Vector3::transform(Vector3::UNIT_X, world, 1.0), Vector3::new(7.0, 6.0, 7.0));
+
+        let m3 = Matrix3::create_rotation(math::PI_OVER_2) * Matrix3::create_translation(Vector2::new(10.0, 0.0));
+        assert!(Vector2::transform(Vector2::UNIT_X, m3, 1.0).approx_eq(Vector2::new(10.0, 1.0), 1e-5));
+    }
+
+    #[test]
+    fn matrix_times_inverse_is_identity() {
+        let samples = [
+            affine_sample(),
+            Matrix4::create_look_at(Vector3::new(1.0, 2.0, -10.0), Vector3::zero(), Vector3::UNIT_Y),
+            Matrix4::create_ortho(1024.0, 768.0, 1.0, 100.0),
+            Matrix4::create_perspective_fov(1.2, 1024.0, 768.0, 10.0, 1000.0),
+            Matrix4::create_simple_view_proj(1024.0, 768.0),
+        ];
+        for m in samples {
+            let inverse = m.inverse().unwrap();
+            assert_matrix_near(m * inverse, Matrix4::identity());
+            assert_matrix_near(inverse * m, Matrix4::identity());
+        }
+    }
+
+    #[test]
+    fn rotations_compose() {
+        assert_matrix_near(
+            Matrix4::create_rotation_z(0.3) * Matrix4::create_rotation_z(0.5),
+            Matrix4::create_rotation_z(0.8),
+        );
+        assert_matrix_near(
+            Matrix4::create_from_quaternion(Quaternion::from_axis_angle(Vector3::UNIT_Z, 0.7)),
+            Matrix4::create_rotation_z(0.7),
+        );
+        assert_matrix_near(
+            Matrix4::create_from_quaternion(Quaternion::from_axis_angle(Vector3::UNIT_X, 0.7)),
+            Matrix4::create_rotation_x(0.7),
+        );
+
+        // concatenate(q, p) is q followed by p, same order as matrices
+        let q = Quaternion::from_axis_angle(Vector3::UNIT_Y, 0.9);
+        let p = Quaternion::from_axis_angle(Vector3::UNIT_X, -0.4);
+        assert_matrix_near(
+            Matrix4::create_from_quaternion(Quaternion::concatenate(q, p)),
+            Matrix4::create_from_quaternion(q) * Matrix4::create_from_quaternion(p),
+        );
+        assert_quaternion_near(Quaternion::concatenate(Quaternion::identity(), q), q);
+    }
+
+    #[test]
+    fn slerp_hits_endpoints_and_midpoint() {
+        let a = Quaternion::from_axis_angle(Vector3::UNIT_Z, 0.2);
+        let b = Quaternion::from_axis_angle(Vector3::UNIT_Z, 1.4);
+        assert_quaternion_near(Quaternion::slerp(a, b, 0.0), a);
+        assert_quaternion_near(Quaternion::slerp(a, b, 1.0), b);
+        assert_quaternion_near(Quaternion::slerp(a, b, 0.5), Quaternion::from_axis_angle(Vector3::UNIT_Z, 0.8));
+
+        // Takes the short way round when the signs disagree
+        assert_quaternion_near(Quaternion::slerp(a, -b, 1.0), b);
+    }
+
+    #[test]
+    fn look_at_and_projections_map_known_points() {
+        let eye = Vector3::new(0.0, 0.0, -10.0);
+        let view = Matrix4::create_look_at(eye, Vector3::zero(), Vector3::UNIT_Y);
+        assert_vector3_near(Vector3::transform(eye, view, 1.0), Vector3::zero());
+        assert_vector3_near(Vector3::transform(Vector3::zero(), view, 1.0), Vector3::new(0.0, 0.0, 10.0));
+
+        // Near plane goes to depth 0, far plane to depth 1
+        let proj = Matrix4::create_perspective_fov(1.2, 1024.0, 768.0, 10.0, 1000.0);
+        let near = Vector3::transform_with_persp_div(Vector3::new(0.0, 0.0, 10.0), proj, 1.0);
+        let far = Vector3::transform_with_persp_div(Vector3::new(0.0, 0.0, 1000.0), proj, 1.0);
+        assert!(near.z.abs() < 1e-5 && (far.z - 1.0).abs() < 1e-5);
+
+        let ortho = Matrix4::create_ortho(1024.0, 768.0, 1.0, 100.0);
+        assert_vector3_near(Vector3::transform(Vector3::new(512.0, -384.0, 100.0), ortho, 1.0), Vector3::new(1.0, -1.0, 1.0));
+    }
+
+    #[test]
+    fn normalized_vectors_have_unit_length() {
+        assert!((Vector2::normalized(Vector2::new(3.0, -4.0)).length() - 1.0).abs() < 1e-6);
+        assert!((Vector3::normalized(Vector3::new(1.0, 2.0, -2.0)).length() - 1.0).abs() < 1e-6);
+        assert!((Quaternion::normalized(Quaternion::new(1.0, 2.0, 3.0, 4.0)).length() - 1.0).abs() < 1e-6);
+        // Zero stays zero instead of turning into NaN
+        assert_eq!(Vector2::normalized(Vector2::zero()), Vector2::zero());
+        assert_eq!(Vector3::normalized(Vector3::zero()), Vector3::zero());
+        let zero = Quaternion::new(0.0, 0.0, 0.0, 0.0);
+        assert_eq!(Quaternion::normalized(zero), zero);
+    }
+
+    fn component() -> impl Strategy<Value = f32> {
+        -100.0f32..100.0
+    }
+
+    fn vector2() -> impl Strategy<Value = Vector2> {
+        (component(), component()).prop_map(|(x, y)| Vector2::new(x, y))
+    }
+
+    fn vector3() -> impl Strategy<Value = Vector3> {
+        (component(), component(), component()).prop_map(|(x, y, z)| Vector3::new(x, y, z))
+    }
+
+    fn angle() -> impl Strategy<Value = f32> {
+        -math::TWO_PI..math::TWO_PI
+    }
+
+    fn unit_quaternion() -> impl Strategy<Value = Quaternion> {
+        (vector3(), angle())
+            .prop_filter("axis must not be zero", |(axis, _)| axis.length() > 1e-3)
+            .prop_map(|(axis, angle)| Quaternion::from_axis_angle(Vector3::normalized(axis), angle))
+    }
+
+    // Scale, rotate, translate: the world transform Actor builds
+    fn world_transform() -> impl Strategy<Value = Matrix4> {
+        (0.1f32..10.0, 0.1f32..10.0, 0.1f32..10.0, unit_quaternion(), vector3()).prop_map(
+            |(sx, sy, sz, rotation, translation)| {
+                Matrix4::create_scale(sx, sy, sz)
+                    * Matrix4::create_from_quaternion(rotation)
+                    * Matrix4::create_translation(translation)
+            },
+        )
+    }
+
+    fn matrix4() -> impl Strategy<Value = Matrix4> {
+        prop::array::uniform4(prop::array::uniform4(-2.0f32..2.0)).prop_map(Matrix4::new)
+    }
+
+    fn matrix_near(a: Matrix4, b: Matrix4) -> bool {
+        (0..4).all(|row| (0..4).all(|col| (a.mat[row][col] - b.mat[row][col]).abs() < 1e-3))
+    }
+
+    fn near(a: f32, b: f32) -> bool {
+        (a - b).abs() <= 1e-3 * a.abs().max(b.abs()).max(1.0)
+    }
+
+    proptest! {
+        #[test]
+        fn prop_world_transform_inverts(m in world_transform()) {
+            let inverse = m.inverse().unwrap();
+            prop_assert!(matrix_near(m * inverse, Matrix4::identity()), "{:?}", m * inverse);
+            prop_assert!(matrix_near(inverse, m.inverse_affine().unwrap()));
+        }
+
+        #[test]
+        fn prop_transform_by_product_is_transform_in_order(a in world_transform(), b in world_transform(), v in vector3()) {
+            let together = Vector3::transform(v, a * b, 1.0);
+            let in_order = Vector3::transform(Vector3::transform(v, a, 1.0), b, 1.0);
+            prop_assert!(together.approx_eq(in_order, 1e-3 * in_order.length().max(1.0)), "{} != {}", together, in_order);
+        }
+
+        #[test]
+        fn prop_transpose_and_determinant(a in matrix4(), b in matrix4()) {
+            prop_assert_eq!(Matrix4::transposed(Matrix4::transposed(a)), a);
+            // Cancellation error grows with the entries, so compare against their size
+            let size = |m: Matrix4| m.mat.iter().flatten().map(|v| v * v).sum::<f32>().sqrt();
+            let tolerance = 1e-5 * (size(a) * size(b)).powi(4).max(1.0);
+            prop_assert!((Matrix4::transposed(a).determinant() - a.determinant()).abs() <= tolerance);
+            prop_assert!(((a * b).determinant() - a.determinant() * b.determinant()).abs() <= tolerance);
+        }
+
+        #[test]
+        fn prop_quaternion_matches_its_matrix(q in unit_quaternion(), v in vector3()) {
+            let by_quaternion = q.rotate(v);
+            let by_matrix = Vector3::transform(v, Matrix4::create_from_quaternion(q), 1.0);
+            prop_assert!(by_quaternion.approx_eq(by_matrix, 1e-3), "{} != {}", by_quaternion, by_matrix);
+            prop_assert!(near(by_quaternion.length(), v.length()));
+        }
+
+        #[test]
+        fn prop_quaternion_concatenation(q in unit_quaternion(), p in unit_quaternion(), v in vector3()) {
+            let together = Quaternion::concatenate(q, p).rotate(v);
+            let in_order = p.rotate(q.rotate(v));
+            prop_assert!(together.approx_eq(in_order, 1e-3), "{} != {}", together, in_order);
+        }
+
+        #[test]
+        fn prop_slerp_stays_unit_and_hits_endpoints(a in unit_quaternion(), b in unit_quaternion(), f in 0.0f32..1.0) {
+            prop_assert!((Quaternion::slerp(a, b, f).length() - 1.0).abs() < 1e-4);
+            prop_assert!(Quaternion::slerp(a, b, 0.0).approx_eq(a, 1e-3));
+            let end = Quaternion::slerp(a, b, 1.0);
+            prop_assert!(end.approx_eq(b, 1e-3) || end.approx_eq(-b, 1e-3));
+        }
+
+        #[test]
+        fn prop_normalized_has_unit_length(v2 in vector2(), v3 in vector3()) {
+            prop_assume!(v2.length() > 1e-3 && v3.length() > 1e-3);
+            prop_assert!((Vector2::normalized(v2).length() - 1.0).abs() < 1e-5);
+            prop_assert!((Vector3::normalized(v3).length() - 1.0).abs() < 1e-5);
+        }
+
+        #[test]
+        fn prop_vector2_rotation(v in vector2(), a in angle()) {
+            let rotated = Vector2::rotate(v, a);
+            prop_assert!(near(rotated.length(), v.length()));
+            prop_assert!(Vector2::rotate(rotated, -a).approx_eq(v, 1e-3));
+            prop_assert!(near(Vector2::cross(v, rotated), v.length_sq() * a.sin()));
+
+            // Same answer as the 2D matrix
+            let by_matrix = Vector2::transform(v, Matrix3::create_rotation(a), 1.0);
+            prop_assert!(rotated.approx_eq(by_matrix, 1e-3));
+        }
+    }
+}
@@ -0,0 +1,253 @@
+// mint / glam との相互変換 (features "mint", "glam")
+//
+// math.rs uses row vectors (v * M, translation in the bottom row) while mint
+// and glam use column vectors (M * v). The stored rows of a Matrix3/Matrix4
+// are therefore the columns of the equivalent column-vector matrix, so a
+// converted matrix transforms points the same way on both sides.
+
+#[cfg(feature = "mint")]
+mod mint_conversions {
+    use crate::math::{Matrix3, Matrix4, Quaternion, Vector2, Vector3};
+
+    impl From<mint::Vector2<f32>> for Vector2 {
+        fn from(v: mint::Vector2<f32>) -> Self {
+            Vector2::new(v.x, v.y)
+        }
+    }
+
+    impl From<Vector2> for mint::Vector2<f32> {
+        fn from(v: Vector2) -> Self {
+            mint::Vector2 { x: v.x, y: v.y }
+        }
+    }
+
+    impl From<mint::Point2<f32>> for Vector2 {
+        fn from(p: mint::Point2<f32>) -> Self {
+            Vector2::new(p.x, p.y)
+        }
+    }
+
+    impl From<Vector2> for mint::Point2<f32> {
+        fn from(v: Vector2) -> Self {
+            mint::Point2 { x: v.x, y: v.y }
+        }
+    }
+
+    impl From<mint::Vector3<f32>> for Vector3 {
+        fn from(v: mint::Vector3<f32>) -> Self {
+            Vector3::new(v.x, v.y, v.z)
+        }
+    }
+
+    impl From<Vector3> for mint::Vector3<f32> {
+        fn from(v: Vector3) -> Self {
+            mint::Vector3 { x: v.x, y: v.y, z: v.z }
+        }
+    }
+
+    impl From<mint::Point3<f32>> for Vector3 {
+        fn from(p: mint::Point3<f32>) -> Self {
+            Vector3::new(p.x, p.y, p.z)
+        }
+    }
+
+    impl From<Vector3> for mint::Point3<f32> {
+        fn from(v: Vector3) -> Self {
+            mint::Point3 { x: v.x, y: v.y, z: v.z }
+        }
+    }
+
+    impl From<mint::Quaternion<f32>> for Quaternion {
+        fn from(q: mint::Quaternion<f32>) -> Self {
+            Quaternion::new(q.v.x, q.v.y, q.v.z, q.s)
+        }
+    }
+
+    impl From<Quaternion> for mint::Quaternion<f32> {
+        fn from(q: Quaternion) -> Self {
+            mint::Quaternion {
+                v: mint::Vector3 { x: q.x, y: q.y, z: q.z },
+                s: q.w,
+            }
+        }
+    }
+
+    // Our rows are the column-vector matrix's columns
+    impl From<mint::ColumnMatrix3<f32>> for Matrix3 {
+        fn from(m: mint::ColumnMatrix3<f32>) -> Self {
+            Matrix3::new(m.into())
+        }
+    }
+
+    impl From<Matrix3> for mint::ColumnMatrix3<f32> {
+        fn from(m: Matrix3) -> Self {
+            m.mat.into()
+        }
+    }
+
+    impl From<mint::RowMatrix3<f32>> for Matrix3 {
+        fn from(m: mint::RowMatrix3<f32>) -> Self {
+            mint::ColumnMatrix3::from(m).into()
+        }
+    }
+
+    impl From<Matrix3> for mint::RowMatrix3<f32> {
+        fn from(m: Matrix3) -> Self {
+            mint::ColumnMatrix3::from(m).into()
+        }
+    }
+
+    impl From<mint::ColumnMatrix4<f32>> for Matrix4 {
+        fn from(m: mint::ColumnMatrix4<f32>) -> Self {
+            Matrix4::new(m.into())
+        }
+    }
+
+    impl From<Matrix4> for mint::ColumnMatrix4<f32> {
+        fn from(m: Matrix4) -> Self {
+            m.mat.into()
+        }
+    }
+
+    impl From<mint::RowMatrix4<f32>> for Matrix4 {
+        fn from(m: mint::RowMatrix4<f32>) -> Self {
+            mint::ColumnMatrix4::from(m).into()
+        }
+    }
+
+    impl From<Matrix4> for mint::RowMatrix4<f32> {
+        fn from(m: Matrix4) -> Self {
+            mint::ColumnMatrix4::from(m).into()
+        }
+    }
+}
+
+#[cfg(feature = "glam")]
+mod glam_conversions {
+    use crate::math::{Matrix3, Matrix4, Quaternion, Vector2, Vector3};
+
+    impl From<glam::Vec2> for Vector2 {
+        fn from(v: glam::Vec2) -> Self {
+            Vector2::new(v.x, v.y)
+        }
+    }
+
+    impl From<Vector2> for glam::Vec2 {
+        fn from(v: Vector2) -> Self {
+            glam::Vec2::new(v.x, v.y)
+        }
+    }
+
+    impl From<glam::Vec3> for Vector3 {
+        fn from(v: glam::Vec3) -> Self {
+            Vector3::new(v.x, v.y, v.z)
+        }
+    }
+
+    impl From<Vector3> for glam::Vec3 {
+        fn from(v: Vector3) -> Self {
+            glam::Vec3::new(v.x, v.y, v.z)
+        }
+    }
+
+    impl From<glam::Quat> for Quaternion {
+        fn from(q: glam::Quat) -> Self {
+            Quaternion::new(q.x, q.y, q.z, q.w)
+        }
+    }
+
+    impl From<Quaternion> for glam::Quat {
+        fn from(q: Quaternion) -> Self {
+            glam::Quat::from_xyzw(q.x, q.y, q.z, q.w)
+        }
+    }
+
+    // Our rows are glam's columns
+    impl From<glam::Mat3> for Matrix3 {
+        fn from(m: glam::Mat3) -> Self {
+            Matrix3::new(m.to_cols_array_2d())
+        }
+    }
+
+    impl From<Matrix3> for glam::Mat3 {
+        fn from(m: Matrix3) -> Self {
+            glam::Mat3::from_cols_array_2d(&m.mat)
+        }
+    }
+
+    impl From<glam::Mat4> for Matrix4 {
+        fn from(m: glam::Mat4) -> Self {
+            Matrix4::new(m.to_cols_array_2d())
+        }
+    }
+
+    impl From<Matrix4> for glam::Mat4 {
+        fn from(m: Matrix4) -> Self {
+            glam::Mat4::from_cols_array_2d(&m.mat)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::math::{ApproxEq, Matrix3, Matrix4, Quaternion, Vector2, Vector3};
+
+    fn world_transform() -> Matrix4 {
+        Matrix4::create_scale(2.0, 3.0, 4.0)
+            * Matrix4::create_from_quaternion(Quaternion::from_axis_angle(Vector3::UNIT_Z, 0.7))
+            * Matrix4::create_translation(Vector3::new(5.0, -6.0, 7.0))
+    }
+
+    #[cfg(feature = "mint")]
+    #[test]
+    fn mint_round_trips_and_stays_column_major() {
+        let m = world_transform();
+        let columns: mint::ColumnMatrix4<f32> = m.into();
+        assert_eq!(columns.w, mint::Vector4 { x: 5.0, y: -6.0, z: 7.0, w: 1.0 });
+        let rows: mint::RowMatrix4<f32> = m.into();
+        assert_eq!(rows.x.w, 5.0);
+        assert_eq!(Matrix4::from(columns), m);
+        assert_eq!(Matrix4::from(rows), m);
+
+        let m3 = Matrix3::create_translation(Vector2::new(3.0, 4.0));
+        let columns: mint::ColumnMatrix3<f32> = m3.into();
+        assert_eq!(columns.z, mint::Vector3 { x: 3.0, y: 4.0, z: 1.0 });
+        assert_eq!(Matrix3::from(mint::RowMatrix3::from(m3)), m3);
+
+        let q = Quaternion::from_axis_angle(Vector3::UNIT_X, 0.3);
+        let mq: mint::Quaternion<f32> = q.into();
+        assert_eq!(mq.s, q.w);
+        assert_eq!(Quaternion::from(mq), q);
+        assert_eq!(Vector3::from(mint::Point3::from(Vector3::new(1.0, 2.0, 3.0))), Vector3::new(1.0, 2.0, 3.0));
+        assert_eq!(Vector2::from(mint::Vector2::from(Vector2::new(1.0, 2.0))), Vector2::new(1.0, 2.0));
+    }
+
+    #[cfg(feature = "glam")]
+    #[test]
+    fn glam_transforms_points_the_same_way() {
+        let m = world_transform();
+        let g = glam::Mat4::from(m);
+        assert_eq!(g.w_axis, glam::Vec4::new(5.0, -6.0, 7.0, 1.0));
+        assert_eq!(Matrix4::from(g), m);
+
+        let point = Vector3::new(1.0, 2.0, 3.0);
+        let ours = Vector3::transform(point, m, 1.0);
+        let theirs = Vector3::from(g.transform_point3(point.into()));
+        assert!(ours.approx_eq(theirs, 1e-4), "{} != {}", ours, theirs);
+
+        // Products keep their meaning: ours left to right is glam's right to left
+        let a = Matrix4::create_rotation_x(0.4);
+        let b = Matrix4::create_translation(Vector3::new(1.0, 0.0, 0.0));
+        assert!((glam::Mat4::from(b) * glam::Mat4::from(a)).abs_diff_eq((a * b).into(), 1e-6));
+
+        let m3 = Matrix3::create_rotation(crate::math::math::PI_OVER_2) * Matrix3::create_translation(Vector2::new(10.0, 0.0));
+        let moved = Vector2::from(glam::Mat3::from(m3).transform_point2(Vector2::UNIT_X.into()));
+        assert!(moved.approx_eq(Vector2::transform(Vector2::UNIT_X, m3, 1.0), 1e-5));
+
+        let q = Quaternion::from_axis_angle(Vector3::normalized(Vector3::new(1.0, 2.0, 3.0)), 1.1);
+        let rotated = Vector3::from(glam::Quat::from(q) * glam::Vec3::from(point));
+        assert!(rotated.approx_eq(q.rotate(point), 1e-5));
+        assert!(glam::Mat4::from(Matrix4::create_from_quaternion(q)).abs_diff_eq(glam::Mat4::from_quat(q.into()), 1e-5));
+        assert_eq!(Quaternion::from(glam::Quat::from(q)), q);
+    }
+}
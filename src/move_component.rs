@@ -1,21 +1,68 @@
-use crate::actor::Actor;
-use std::rc::Rc;
-use std::cell::RefCell;
-use crate::component::{Component, BaseComponent};
+use crate::actor::{ActorId, State};
+use crate::game::Game;
+use crate::component::{Component, BaseComponent, ComponentId};
+use crate::math::{math, Vector2};
+use crate::world_bounds::WorldBounds;
+
+/// What happens when a moving actor reaches the edge of the world bounds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EdgePolicy {
+    /// Reappear on the opposite edge.
+    Wrap,
+    /// Stop at the edge.
+    Clamp,
+    /// Reflect the heading (or velocity in physics mode) off the edge.
+    Bounce,
+    /// Mark the actor dead.
+    Kill,
+}
 
 #[derive(Clone)]
 pub struct MoveComponent {
     base: BaseComponent,
     angular_speed: f32,
     forward_speed: f32,
+    edge_policy: EdgePolicy,
+    // Newtonian mode: speeds above are ignored and the actor moves by
+    // integrating the forces applied each frame
+    physics_enabled: bool,
+    mass: f32,
+    velocity: Vector2,
+    angular_velocity: f32,
+    sum_of_forces: Vector2,
+    sum_of_torques: f32,
+    linear_drag: f32,
+    angular_drag: f32,
+    max_speed: f32,
+    max_angular_speed: f32,
+    // Bounciness for collision response, 0 (dead stop) to 1 (elastic)
+    restitution: f32,
 }
 
 impl MoveComponent {
-    pub fn new(owner: Rc<RefCell<Actor>>, update_order: i32) -> Self {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(game: &mut Game, owner: ActorId, update_order: i32) -> ComponentId {
+        game.add_component(Box::new(MoveComponent::unregistered(owner, update_order)))
+    }
+
+    // For components that drive a MoveComponent of their own
+    pub fn unregistered(owner: ActorId, update_order: i32) -> Self {
         MoveComponent {
             base: BaseComponent::new(owner, update_order),
             angular_speed: 0.0,
             forward_speed: 0.0,
+            edge_policy: EdgePolicy::Wrap,
+            physics_enabled: false,
+            mass: 1.0,
+            velocity: Vector2::zero(),
+            angular_velocity: 0.0,
+            sum_of_forces: Vector2::zero(),
+            sum_of_torques: 0.0,
+            linear_drag: 0.0,
+            angular_drag: 0.0,
+            max_speed: f32::MAX,
+            max_angular_speed: f32::MAX,
+            restitution: 1.0,
         }
     }
 
@@ -35,43 +82,362 @@ impl MoveComponent {
     pub fn set_forward_speed(&mut self, speed: f32) {
         self.forward_speed = speed;
     }
+
+    pub fn get_edge_policy(&self) -> EdgePolicy {
+        self.edge_policy
+    }
+
+    pub fn set_edge_policy(&mut self, policy: EdgePolicy) {
+        self.edge_policy = policy;
+    }
+
+    pub fn is_physics_enabled(&self) -> bool {
+        self.physics_enabled
+    }
+
+    pub fn set_physics_enabled(&mut self, enabled: bool) {
+        self.physics_enabled = enabled;
+    }
+
+    pub fn get_mass(&self) -> f32 {
+        self.mass
+    }
+
+    pub fn set_mass(&mut self, mass: f32) {
+        self.mass = mass.max(f32::EPSILON);
+    }
+
+    pub fn get_velocity(&self) -> Vector2 {
+        self.velocity
+    }
+
+    pub fn set_velocity(&mut self, velocity: Vector2) {
+        self.velocity = velocity;
+    }
+
+    pub fn get_angular_velocity(&self) -> f32 {
+        self.angular_velocity
+    }
+
+    pub fn set_angular_velocity(&mut self, velocity: f32) {
+        self.angular_velocity = velocity;
+    }
+
+    // Drag is the fraction of velocity lost per second
+    pub fn set_linear_drag(&mut self, drag: f32) {
+        self.linear_drag = drag;
+    }
+
+    pub fn set_angular_drag(&mut self, drag: f32) {
+        self.angular_drag = drag;
+    }
+
+    pub fn set_max_speed(&mut self, speed: f32) {
+        self.max_speed = speed;
+    }
+
+    pub fn set_max_angular_speed(&mut self, speed: f32) {
+        self.max_angular_speed = speed;
+    }
+
+    pub fn get_restitution(&self) -> f32 {
+        self.restitution
+    }
+
+    pub fn set_restitution(&mut self, restitution: f32) {
+        self.restitution = restitution.clamp(0.0, 1.0);
+    }
+
+    // Velocity in either mode: the heading times forward speed when
+    // kinematic
+    pub fn get_current_velocity(&self, forward: Vector2) -> Vector2 {
+        if self.physics_enabled {
+            self.velocity
+        } else {
+            forward * self.forward_speed
+        }
+    }
+
+    // Returns the heading to give the owner when kinematic, since there
+    // the direction of travel is the actor's rotation
+    pub fn set_current_velocity(&mut self, velocity: Vector2) -> Option<f32> {
+        if self.physics_enabled {
+            self.velocity = velocity;
+            return None;
+        }
+        self.forward_speed = velocity.length();
+        (self.forward_speed > f32::EPSILON).then(|| velocity.y.atan2(velocity.x))
+    }
+
+    // Forces and torques accumulate until the next update
+    pub fn add_force(&mut self, force: Vector2) {
+        self.sum_of_forces += force;
+    }
+
+    pub fn add_torque(&mut self, torque: f32) {
+        self.sum_of_torques += torque;
+    }
+
+    // Instant change of momentum
+    pub fn add_impulse(&mut self, impulse: Vector2) {
+        self.velocity += impulse * (1.0 / self.mass);
+    }
+
+    // Drop all motion, e.g. when the owner respawns
+    pub fn stop(&mut self) {
+        self.forward_speed = 0.0;
+        self.angular_speed = 0.0;
+        self.velocity = Vector2::zero();
+        self.angular_velocity = 0.0;
+        self.sum_of_forces = Vector2::zero();
+        self.sum_of_torques = 0.0;
+    }
+
+    fn integrate(&mut self, delta_time: f32) {
+        // Semi-implicit Euler: velocity first, then position uses it
+        let inv_mass = 1.0 / self.mass;
+        self.velocity += self.sum_of_forces * (inv_mass * delta_time);
+        self.velocity *= (1.0 - self.linear_drag * delta_time).max(0.0);
+        let speed = self.velocity.length();
+        if speed > self.max_speed {
+            self.velocity *= self.max_speed / speed;
+        }
+
+        self.angular_velocity += self.sum_of_torques * inv_mass * delta_time;
+        self.angular_velocity *= (1.0 - self.angular_drag * delta_time).max(0.0);
+        self.angular_velocity = self
+            .angular_velocity
+            .clamp(-self.max_angular_speed, self.max_angular_speed);
+
+        self.sum_of_forces = Vector2::zero();
+        self.sum_of_torques = 0.0;
+    }
 }
 
-impl Component for MoveComponent {
-    fn new(owner: Rc<RefCell<Actor>>, update_order: i32) -> Self {
-        MoveComponent::new(owner, update_order)
+// Where an actor that moved to `pos` ends up after the edge policy
+struct EdgeResult {
+    pos: Vector2,
+    // Bounced off a left/right or top/bottom edge
+    flip_x: bool,
+    flip_y: bool,
+}
+
+// `None` means the actor left the world and should die.
+fn apply_edge_policy(policy: EdgePolicy, bounds: &WorldBounds, mut pos: Vector2) -> Option<EdgeResult> {
+    let mut flip_x = false;
+    let mut flip_y = false;
+    if bounds.contains(pos) {
+        return Some(EdgeResult { pos, flip_x, flip_y });
     }
 
-    fn update(&mut self, delta_time: f32) {
-        if self.angular_speed.abs() > f32::EPSILON {
-            let mut rot = self.base.owner.borrow().get_rotation();
-            rot += self.angular_speed * delta_time;
-            self.base.owner.borrow_mut().set_rotation(rot);
+    let min = bounds.get_min();
+    let max = bounds.get_max();
+    match policy {
+        EdgePolicy::Wrap => {
+            if pos.x < min.x { pos.x += bounds.get_width(); }
+            else if pos.x > max.x { pos.x -= bounds.get_width(); }
+
+            if pos.y < min.y { pos.y += bounds.get_height(); }
+            else if pos.y > max.y { pos.y -= bounds.get_height(); }
         }
+        EdgePolicy::Clamp => {
+            pos.x = pos.x.clamp(min.x, max.x);
+            pos.y = pos.y.clamp(min.y, max.y);
+        }
+        EdgePolicy::Bounce => {
+            // Mirror the overshoot back inside and flip the matching axis
+            if pos.x < min.x || pos.x > max.x {
+                let edge = if pos.x < min.x { min.x } else { max.x };
+                pos.x = 2.0 * edge - pos.x;
+                flip_x = true;
+            }
+            if pos.y < min.y || pos.y > max.y {
+                let edge = if pos.y < min.y { min.y } else { max.y };
+                pos.y = 2.0 * edge - pos.y;
+                flip_y = true;
+            }
+            pos.x = pos.x.clamp(min.x, max.x);
+            pos.y = pos.y.clamp(min.y, max.y);
+        }
+        EdgePolicy::Kill => return None,
+    }
+    Some(EdgeResult { pos, flip_x, flip_y })
+}
+
+impl Component for MoveComponent {
+    fn update(&mut self, game: &mut Game, delta_time: f32) {
+        let bounds = *game.get_world_bounds();
+        let Some(owner) = game.get_actor_mut(self.base.owner) else {
+            return;
+        };
 
-        if self.forward_speed.abs() > f32::EPSILON {
-            let mut pos = self.base.owner.borrow().get_position();
-            let forward = self.base.owner.borrow().get_forward();
-            pos += forward * self.forward_speed * delta_time;
+        let (angular_speed, velocity) = if self.physics_enabled {
+            self.integrate(delta_time);
+            (self.angular_velocity, self.velocity)
+        } else {
+            (self.angular_speed, owner.get_forward() * self.forward_speed)
+        };
 
-            // 画面外に出た場合のスクリーンラップ処理（Asteroid専用）
-            if pos.x < 0.0 { pos.x = 1022.0; }
-            else if pos.x > 1024.0 { pos.x = 2.0; }
+        if angular_speed.abs() > f32::EPSILON {
+            let mut rot = owner.get_rotation();
+            rot += angular_speed * delta_time;
+            owner.set_rotation(rot);
+        }
+
+        if velocity.length_sq() > f32::EPSILON {
+            let mut pos = owner.get_position();
+            pos += velocity * delta_time;
 
-            if pos.y < 0.0 { pos.y = 766.0; }
-            else if pos.y > 768.0 { pos.y = 2.0; }
+            // 画面外に出た場合の処理
+            let Some(edge) = apply_edge_policy(self.edge_policy, &bounds, pos) else {
+                owner.set_state(State::Dead);
+                return;
+            };
+            if edge.pos != pos {
+                owner.reset_interpolation();
+            }
+            owner.set_position(edge.pos);
 
-            self.base.owner.borrow_mut().set_position(pos);
+            if self.physics_enabled {
+                if edge.flip_x { self.velocity.x = -self.velocity.x; }
+                if edge.flip_y { self.velocity.y = -self.velocity.y; }
+            } else {
+                let mut rot = owner.get_rotation();
+                if edge.flip_x { rot = math::PI - rot; }
+                if edge.flip_y { rot = -rot; }
+                owner.set_rotation(rot);
+            }
         }
     }
 
     fn get_update_order(&self) -> i32 {
         self.base.get_update_order()
     }
+
+    fn get_owner(&self) -> ActorId {
+        self.base.get_owner()
+    }
 }
 
-impl Drop for MoveComponent {
-    fn drop(&mut self) {
-        self.base.owner.borrow_mut().remove_component(Rc::new(RefCell::new(self.clone())));
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actor::Actor;
+    use crate::game::GameConfig;
+
+    fn bounds() -> WorldBounds {
+        WorldBounds::from_size(1024.0, 768.0)
+    }
+
+    fn moving_actor(game: &mut Game, position: Vector2, rotation: f32) -> (ActorId, ComponentId) {
+        let actor = Actor::new(game);
+        let owner = game.get_actor_mut(actor).unwrap();
+        owner.set_position(position);
+        owner.set_rotation(rotation);
+        (actor, MoveComponent::new(game, actor, 10))
+    }
+
+    #[test]
+    fn wrap_reappears_on_the_opposite_edge() {
+        let edge = apply_edge_policy(EdgePolicy::Wrap, &bounds(), Vector2::new(-515.0, 390.0)).unwrap();
+        assert_eq!(edge.pos, Vector2::new(509.0, -378.0));
+        assert!(!edge.flip_x && !edge.flip_y);
+    }
+
+    #[test]
+    fn clamp_and_bounce_stay_inside() {
+        let outside = Vector2::new(520.0, 0.0);
+        let edge = apply_edge_policy(EdgePolicy::Clamp, &bounds(), outside).unwrap();
+        assert_eq!(edge.pos, Vector2::new(512.0, 0.0));
+
+        let edge = apply_edge_policy(EdgePolicy::Bounce, &bounds(), outside).unwrap();
+        assert_eq!(edge.pos, Vector2::new(504.0, 0.0));
+        assert!(edge.flip_x && !edge.flip_y);
+    }
+
+    #[test]
+    fn bounce_reflects_heading_or_velocity() {
+        let mut game = Game::with_config(GameConfig::headless());
+        let (actor, move_component) = moving_actor(&mut game, Vector2::new(510.0, 0.0), 0.0);
+        let move_comp = game.get_component_mut::<MoveComponent>(move_component).unwrap();
+        move_comp.set_forward_speed(600.0);
+        move_comp.set_edge_policy(EdgePolicy::Bounce);
+
+        game.update_game(0.01);
+        let owner = game.get_actor(actor).unwrap();
+        assert_eq!(owner.get_position(), Vector2::new(508.0, 0.0));
+        assert!((owner.get_rotation() - math::PI).abs() < 1e-6);
+
+        let (actor, move_component) = moving_actor(&mut game, Vector2::new(0.0, -380.0), 0.0);
+        let move_comp = game.get_component_mut::<MoveComponent>(move_component).unwrap();
+        move_comp.set_physics_enabled(true);
+        move_comp.set_edge_policy(EdgePolicy::Bounce);
+        move_comp.set_velocity(Vector2::new(100.0, -600.0));
+
+        game.update_game(0.01);
+        let move_comp = game.get_component::<MoveComponent>(move_component).unwrap();
+        assert_eq!(move_comp.get_velocity(), Vector2::new(100.0, 600.0));
+        assert_eq!(game.get_actor(actor).unwrap().get_rotation(), 0.0);
+    }
+
+    #[test]
+    fn physics_mode_keeps_drifting_after_forces_stop() {
+        let mut game = Game::with_config(GameConfig::headless());
+        let (actor, move_component) = moving_actor(&mut game, Vector2::zero(), 0.0);
+        let move_comp = game.get_component_mut::<MoveComponent>(move_component).unwrap();
+        move_comp.set_physics_enabled(true);
+        move_comp.set_mass(2.0);
+        move_comp.add_force(Vector2::new(200.0, 0.0));
+
+        // a = F / m = 100, so one 0.1s step reaches 10 and moves 1
+        game.update_game(0.1);
+        let move_comp = game.get_component::<MoveComponent>(move_component).unwrap();
+        assert!((move_comp.get_velocity().x - 10.0).abs() < 1e-4);
+        assert!((game.get_actor(actor).unwrap().get_position().x - 1.0).abs() < 1e-4);
+
+        // No more force: keeps going at the same speed
+        game.update_game(0.1);
+        assert!((game.get_actor(actor).unwrap().get_position().x - 2.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn drag_and_max_speed_limit_velocity() {
+        let mut game = Game::with_config(GameConfig::headless());
+        let (_, move_component) = moving_actor(&mut game, Vector2::zero(), 0.0);
+        let move_comp = game.get_component_mut::<MoveComponent>(move_component).unwrap();
+        move_comp.set_physics_enabled(true);
+        move_comp.set_max_speed(50.0);
+        move_comp.add_impulse(Vector2::new(0.0, 80.0));
+        move_comp.add_torque(10.0);
+        move_comp.set_angular_drag(5.0);
+
+        game.update_game(0.1);
+        let move_comp = game.get_component_mut::<MoveComponent>(move_component).unwrap();
+        assert!((move_comp.get_velocity().length() - 50.0).abs() < 1e-4);
+        // 10 * 0.1 = 1, then half of it is lost to drag
+        assert!((move_comp.get_angular_velocity() - 0.5).abs() < 1e-4);
+
+        move_comp.set_linear_drag(2.0);
+        game.update_game(0.1);
+        let move_comp = game.get_component::<MoveComponent>(move_component).unwrap();
+        assert!((move_comp.get_velocity().y - 40.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn kill_marks_the_owner_dead() {
+        let mut game = Game::with_config(GameConfig {
+            world_bounds: Some(WorldBounds::new(Vector2::zero(), Vector2::new(100.0, 100.0))),
+            ..GameConfig::headless()
+        });
+        let (actor, move_component) = moving_actor(&mut game, Vector2::new(95.0, 50.0), 0.0);
+        let move_comp = game.get_component_mut::<MoveComponent>(move_component).unwrap();
+        move_comp.set_forward_speed(100.0);
+        move_comp.set_edge_policy(EdgePolicy::Kill);
+
+        game.update_game(0.01);
+        assert!(game.get_actor(actor).is_some());
+
+        game.update_game(0.1);
+        assert!(game.get_actor(actor).is_none());
     }
 }
@@ -1,34 +1,302 @@
-use rand::{thread_rng, Rng};
-use crate::math::{Vector2, Vector3};
+use rand::{RngCore, SeedableRng};
+use rand_pcg::Pcg32;
+use crate::math::{math, Vector2, Vector3};
 
-pub struct Random;
+/// Saved position in a `Random` sequence, from `Random::save_state`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RandomState(Pcg32);
+
+/// Seeded random numbers. `Game` owns one so a seed reproduces a whole
+/// session; PCG gives the same sequence on every platform. Every value is
+/// built from the raw PCG output rather than rand's distributions, so a
+/// seed keeps its sequence across rand releases too.
+#[derive(Clone, Debug)]
+pub struct Random {
+    generator: Pcg32,
+    seed: u64,
+}
 
 impl Random {
-    pub fn get_float() -> f32 {
-        Random::get_float_range(0.0, 1.0)
+    pub fn new(seed: u64) -> Self {
+        Random {
+            generator: Pcg32::seed_from_u64(seed),
+            seed,
+        }
+    }
+
+    // Fresh seed from the OS, for when nothing needs to be reproduced
+    pub fn generate_seed() -> u64 {
+        rand::random()
+    }
+
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    // Same seed, same sequence
+    pub fn reseed(&mut self, seed: u64) {
+        *self = Random::new(seed);
+    }
+
+    pub fn save_state(&self) -> RandomState {
+        RandomState(self.generator.clone())
+    }
+
+    pub fn restore_state(&mut self, state: &RandomState) {
+        self.generator = state.0.clone();
+    }
+
+    // 0..1, from the top 24 bits (all an f32 mantissa can hold)
+    pub fn get_float(&mut self) -> f32 {
+        (self.generator.next_u32() >> 8) as f32 / (1 << 24) as f32
+    }
+
+    // min..max; an empty range gives min
+    pub fn get_float_range(&mut self, min: f32, max: f32) -> f32 {
+        if min >= max || min.is_nan() || max.is_nan() {
+            return min;
+        }
+        let value = min + (max - min) * self.get_float();
+        // Rounding can land on max itself
+        if value < max { value } else { max.next_down().max(min) }
+    }
+
+    // min..=max; an empty range gives min
+    pub fn get_int_range(&mut self, min: i32, max: i32) -> i32 {
+        if max < min {
+            return min;
+        }
+        let span = (max as i64 - min as i64 + 1) as u64;
+        if span > u32::MAX as u64 {
+            return self.generator.next_u32() as i32;
+        }
+        (min as i64 + self.get_below(span as u32) as i64) as i32
     }
 
-    pub fn get_float_range(min: f32, max: f32) -> f32 {
-        let mut rng = thread_rng();
-        rng.gen_range(min..max)
+    pub fn get_bool(&mut self) -> bool {
+        self.get_chance(0.5)
     }
 
-    pub fn get_int_range(min: i32, max: i32) -> i32 {
-        let mut rng = thread_rng();
-        rng.gen_range(min..=max)
+    // True with the given probability, 0..1
+    pub fn get_chance(&mut self, probability: f32) -> bool {
+        self.get_float() < probability
     }
 
-    pub fn get_vector2(min: Vector2, max: Vector2) -> Vector2 {
-        let r = Vector2::new(Random::get_float(), Random::get_float());
+    pub fn get_vector2(&mut self, min: Vector2, max: Vector2) -> Vector2 {
+        let r = Vector2::new(self.get_float(), self.get_float());
         min + (max - min) * r
     }
 
-    pub fn get_vector3(min: Vector3, max: Vector3) -> Vector3 {
+    pub fn get_vector3(&mut self, min: Vector3, max: Vector3) -> Vector3 {
         let r = Vector3::new(
-            Random::get_float(),
-            Random::get_float(),
-            Random::get_float(),
+            self.get_float(),
+            self.get_float(),
+            self.get_float(),
         );
         min + (max - min) * r
     }
+
+    // 長さ1のランダムな方向
+    pub fn get_unit_vector2(&mut self) -> Vector2 {
+        Vector2::rotate(Vector2::UNIT_X, self.get_float_range(0.0, math::TWO_PI))
+    }
+
+    // Normal distribution (Box-Muller)
+    pub fn get_gaussian(&mut self, mean: f32, std_dev: f32) -> f32 {
+        // 1 - u keeps the log away from zero
+        let u1 = 1.0 - self.get_float();
+        let u2 = self.get_float();
+        let z = (-2.0 * u1.ln()).sqrt() * (math::TWO_PI * u2).cos();
+        mean + z * std_dev
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            return None;
+        }
+        items.get(self.get_index(items.len()))
+    }
+
+    // Index picked in proportion to its weight. Negative and NaN weights
+    // count as 0, infinite ones share every pick between them; None if
+    // nothing has any weight.
+    pub fn get_weighted_index(&mut self, weights: &[f32]) -> Option<usize> {
+        let infinite: Vec<usize> = (0..weights.len())
+            .filter(|&index| weights[index] == f32::INFINITY)
+            .collect();
+        if let Some(&index) = self.choose(&infinite) {
+            return Some(index);
+        }
+        // Summed in f64 so large finite weights can't add up to infinity
+        let total: f64 = weights.iter().map(|weight| weight.max(0.0) as f64).sum();
+        if total <= 0.0 {
+            return None;
+        }
+        let mut pick = self.get_float() as f64 * total;
+        for (index, weight) in weights.iter().enumerate() {
+            let weight = weight.max(0.0) as f64;
+            if pick < weight {
+                return Some(index);
+            }
+            pick -= weight;
+        }
+        // Rounding left us past the end
+        weights.iter().rposition(|&weight| weight > 0.0)
+    }
+
+    pub fn choose_weighted<'a, T>(&mut self, items: &'a [T], weight: impl Fn(&T) -> f32) -> Option<&'a T> {
+        let weights: Vec<f32> = items.iter().map(weight).collect();
+        self.get_weighted_index(&weights).map(|index| &items[index])
+    }
+
+    // Fisher-Yates on top of get_index, so the order only depends on PCG
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.get_index(i + 1);
+            items.swap(i, j);
+        }
+    }
+
+    fn get_index(&mut self, len: usize) -> usize {
+        self.get_below(len as u32) as usize
+    }
+
+    // Unbiased 0..bound by rejection sampling
+    fn get_below(&mut self, bound: u32) -> u32 {
+        // 2^32 % bound: values below it would favour the low results
+        let threshold = bound.wrapping_neg() % bound;
+        loop {
+            let value = self.generator.next_u32();
+            if value >= threshold {
+                return value % bound;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_sequence() {
+        let mut a = Random::new(42);
+        let mut b = Random::new(42);
+        let mut c = Random::new(43);
+        let sequence_a: Vec<f32> = (0..10).map(|_| a.get_float()).collect();
+        let sequence_b: Vec<f32> = (0..10).map(|_| b.get_float()).collect();
+        let sequence_c: Vec<f32> = (0..10).map(|_| c.get_float()).collect();
+        assert_eq!(sequence_a, sequence_b);
+        assert_ne!(sequence_a, sequence_c);
+
+        a.reseed(42);
+        assert_eq!(a.get_float(), sequence_a[0]);
+        assert_eq!(a.get_seed(), 42);
+    }
+
+    #[test]
+    fn restoring_state_repeats_the_sequence() {
+        let mut random = Random::new(1);
+        random.get_float();
+        let state = random.save_state();
+        let first: Vec<i32> = (0..10).map(|_| random.get_int_range(0, 100)).collect();
+        random.restore_state(&state);
+        let second: Vec<i32> = (0..10).map(|_| random.get_int_range(0, 100)).collect();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn ranges_and_vectors_stay_in_bounds() {
+        let mut random = Random::new(2);
+        for _ in 0..1000 {
+            let int = random.get_int_range(-2, 2);
+            assert!((-2..=2).contains(&int));
+            let float = random.get_float_range(5.0, 6.0);
+            assert!((5.0..6.0).contains(&float));
+            assert!((random.get_unit_vector2().length() - 1.0).abs() < 1e-5);
+        }
+        // Inclusive range reaches both ends
+        let ints: Vec<i32> = (0..200).map(|_| random.get_int_range(0, 3)).collect();
+        assert!(ints.contains(&0) && ints.contains(&3));
+        let full = random.get_int_range(i32::MIN, i32::MAX);
+        assert!((i32::MIN..=i32::MAX).contains(&full));
+    }
+
+    #[test]
+    fn empty_ranges_give_the_minimum() {
+        let mut random = Random::new(6);
+        assert_eq!(random.get_float_range(3.0, 3.0), 3.0);
+        assert_eq!(random.get_float_range(4.0, 1.0), 4.0);
+        assert_eq!(random.get_int_range(5, 2), 5);
+        assert_eq!(random.get_int_range(-1, -1), -1);
+    }
+
+    #[test]
+    fn chance_follows_the_probability() {
+        let mut random = Random::new(8);
+        assert!((0..100).all(|_| !random.get_chance(0.0)));
+        assert!((0..100).all(|_| random.get_chance(1.0)));
+        let heads = (0..4000).filter(|_| random.get_bool()).count();
+        assert!((1800..2200).contains(&heads), "{}", heads);
+    }
+
+    #[test]
+    fn choose_and_shuffle() {
+        let mut random = Random::new(3);
+        let empty: [i32; 0] = [];
+        assert_eq!(random.choose(&empty), None);
+        assert!([1, 2, 3].contains(random.choose(&[1, 2, 3]).unwrap()));
+
+        let mut items: Vec<i32> = (0..20).collect();
+        random.shuffle(&mut items);
+        assert_ne!(items, (0..20).collect::<Vec<_>>());
+        items.sort();
+        assert_eq!(items, (0..20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn shuffle_order_is_pinned_to_the_seed() {
+        // Changes only if PCG itself or our sampling changes
+        let mut random = Random::new(7);
+        let mut items: Vec<i32> = (0..8).collect();
+        random.shuffle(&mut items);
+        assert_eq!(items, vec![5, 6, 3, 0, 4, 7, 2, 1]);
+    }
+
+    #[test]
+    fn weighted_pick_follows_the_weights() {
+        let mut random = Random::new(4);
+        assert_eq!(random.get_weighted_index(&[]), None);
+        assert_eq!(random.get_weighted_index(&[0.0, -1.0]), None);
+
+        let mut counts = [0; 3];
+        for _ in 0..4000 {
+            counts[random.get_weighted_index(&[1.0, 0.0, 3.0]).unwrap()] += 1;
+        }
+        assert_eq!(counts[1], 0);
+        assert!((2800..3200).contains(&counts[2]), "{:?}", counts);
+
+        // Infinite weights take every pick
+        for _ in 0..100 {
+            let index = random.get_weighted_index(&[1.0, f32::INFINITY, f32::MAX, f32::INFINITY]).unwrap();
+            assert!(index == 1 || index == 3);
+        }
+        // Finite weights too big to sum in f32
+        let index = random.get_weighted_index(&[f32::MAX, f32::MAX, f32::NAN]).unwrap();
+        assert!(index < 2);
+
+        let picked = random.choose_weighted(&["never", "always"], |&name| if name == "always" { 1.0 } else { 0.0 });
+        assert_eq!(picked, Some(&"always"));
+    }
+
+    #[test]
+    fn gaussian_has_the_requested_mean_and_spread() {
+        let mut random = Random::new(5);
+        let samples: Vec<f32> = (0..10000).map(|_| random.get_gaussian(10.0, 2.0)).collect();
+        let mean = samples.iter().sum::<f32>() / samples.len() as f32;
+        let variance = samples.iter().map(|s| (s - mean) * (s - mean)).sum::<f32>() / samples.len() as f32;
+        assert!((mean - 10.0).abs() < 0.1, "{}", mean);
+        assert!((variance.sqrt() - 2.0).abs() < 0.1, "{}", variance.sqrt());
+        assert!(samples.iter().all(|s| s.is_finite()));
+    }
 }
@@ -0,0 +1,251 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+/// Generational index into a `Registry`. Once the value it names is removed
+/// the slot's generation moves on, so a stale handle simply finds nothing
+/// instead of aliasing whatever reuses the slot.
+pub struct Handle<T> {
+    index: u32,
+    generation: u32,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Handle<T> {
+    fn new(index: u32, generation: u32) -> Self {
+        Handle {
+            index,
+            generation,
+            marker: PhantomData,
+        }
+    }
+}
+
+// Implemented by hand so handles stay Copy/Eq/Hash whatever T is
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Handle<T> {}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index && self.generation == other.generation
+    }
+}
+
+impl<T> Eq for Handle<T> {}
+
+// Arbitrary but fixed, so maps keyed by handles iterate the same every run
+impl<T> PartialOrd for Handle<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Handle<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.index, self.generation).cmp(&(other.index, other.generation))
+    }
+}
+
+impl<T> Hash for Handle<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+        self.generation.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Handle({}v{})", self.index, self.generation)
+    }
+}
+
+enum Slot<T> {
+    Vacant,
+    Occupied(T),
+    // Temporarily taken out with `take`, still counts as alive
+    CheckedOut,
+}
+
+struct Entry<T> {
+    generation: u32,
+    slot: Slot<T>,
+}
+
+/// Slot map that owns its values and hands out `Handle`s to them.
+pub struct Registry<T> {
+    entries: Vec<Entry<T>>,
+    free_indices: Vec<u32>,
+    len: usize,
+}
+
+impl<T> Registry<T> {
+    pub fn new() -> Self {
+        Registry {
+            entries: Vec::new(),
+            free_indices: Vec::new(),
+            len: 0,
+        }
+    }
+
+    pub fn insert(&mut self, value: T) -> Handle<T> {
+        self.len += 1;
+        if let Some(index) = self.free_indices.pop() {
+            let entry = &mut self.entries[index as usize];
+            entry.slot = Slot::Occupied(value);
+            Handle::new(index, entry.generation)
+        } else {
+            self.entries.push(Entry {
+                generation: 0,
+                slot: Slot::Occupied(value),
+            });
+            Handle::new(self.entries.len() as u32 - 1, 0)
+        }
+    }
+
+    /// Remove the value behind `handle`. Returns `None` if the handle is
+    /// stale, or if the value is checked out (it is dropped on `restore`).
+    pub fn remove(&mut self, handle: Handle<T>) -> Option<T> {
+        let entry = self.live_entry_mut(handle)?;
+        let slot = std::mem::replace(&mut entry.slot, Slot::Vacant);
+        entry.generation = entry.generation.wrapping_add(1);
+        self.free_indices.push(handle.index);
+        self.len -= 1;
+
+        match slot {
+            Slot::Occupied(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn contains(&self, handle: Handle<T>) -> bool {
+        self.live_entry(handle).is_some()
+    }
+
+    pub fn get(&self, handle: Handle<T>) -> Option<&T> {
+        match &self.live_entry(handle)?.slot {
+            Slot::Occupied(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, handle: Handle<T>) -> Option<&mut T> {
+        match &mut self.live_entry_mut(handle)?.slot {
+            Slot::Occupied(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Check a value out so it can be used alongside a `&mut` to the owner
+    /// of this registry. The handle stays valid until `restore`.
+    pub fn take(&mut self, handle: Handle<T>) -> Option<T> {
+        let entry = self.live_entry_mut(handle)?;
+        match std::mem::replace(&mut entry.slot, Slot::CheckedOut) {
+            Slot::Occupied(value) => Some(value),
+            other => {
+                entry.slot = other;
+                None
+            }
+        }
+    }
+
+    /// Return a value from `take`. If it was removed in the meantime the
+    /// value is dropped here.
+    pub fn restore(&mut self, handle: Handle<T>, value: T) {
+        if let Some(entry) = self.live_entry_mut(handle) {
+            if let Slot::CheckedOut = entry.slot {
+                entry.slot = Slot::Occupied(value);
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Handle<T>, &T)> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| match &entry.slot {
+                Slot::Occupied(value) => Some((Handle::new(index as u32, entry.generation), value)),
+                _ => None,
+            })
+    }
+
+    fn live_entry(&self, handle: Handle<T>) -> Option<&Entry<T>> {
+        self.entries
+            .get(handle.index as usize)
+            .filter(|entry| entry.generation == handle.generation)
+            .filter(|entry| !matches!(entry.slot, Slot::Vacant))
+    }
+
+    fn live_entry_mut(&mut self, handle: Handle<T>) -> Option<&mut Entry<T>> {
+        self.entries
+            .get_mut(handle.index as usize)
+            .filter(|entry| entry.generation == handle.generation)
+            .filter(|entry| !matches!(entry.slot, Slot::Vacant))
+    }
+}
+
+impl<T> Default for Registry<T> {
+    fn default() -> Self {
+        Registry::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stale_handle_does_not_see_reused_slot() {
+        let mut registry = Registry::new();
+        let first = registry.insert("first");
+        assert_eq!(registry.remove(first), Some("first"));
+
+        let second = registry.insert("second");
+        assert_eq!(first.index, second.index);
+        assert_eq!(registry.get(first), None);
+        assert_eq!(registry.get(second), Some(&"second"));
+        assert_eq!(registry.remove(first), None);
+        assert_eq!(registry.len(), 1);
+    }
+
+    #[test]
+    fn checked_out_value_is_still_alive() {
+        let mut registry = Registry::new();
+        let handle = registry.insert(7);
+
+        let value = registry.take(handle).unwrap();
+        assert!(registry.contains(handle));
+        assert_eq!(registry.get(handle), None);
+        assert_eq!(registry.take(handle), None);
+
+        registry.restore(handle, value + 1);
+        assert_eq!(registry.get(handle), Some(&8));
+    }
+
+    #[test]
+    fn removing_a_checked_out_value_drops_it_on_restore() {
+        let mut registry = Registry::new();
+        let handle = registry.insert(7);
+
+        let value = registry.take(handle).unwrap();
+        assert_eq!(registry.remove(handle), None);
+        assert!(!registry.contains(handle));
+
+        registry.restore(handle, value);
+        assert!(!registry.contains(handle));
+        assert!(registry.is_empty());
+        assert_eq!(registry.iter().count(), 0);
+    }
+}
@@ -1,4 +1,3 @@
-use gl;
 use std::ffi::CString;
 use std::fs::File;
 use std::io::Read;
@@ -126,6 +125,12 @@ impl Shader {
     }
 }
 
+impl Default for Shader {
+    fn default() -> Self {
+        Shader::new()
+    }
+}
+
 impl Drop for Shader {
     fn drop(&mut self) {
         self.unload();
@@ -0,0 +1,374 @@
+use crate::actor::{Actor, ActorBehavior, ActorId};
+use crate::circle_component::CircleComponent;
+use crate::collision_world::layers;
+use crate::component::ComponentId;
+use crate::game::Game;
+use crate::input_component::InputComponent;
+use crate::input_map::{Action, InputMap, InputState};
+use crate::laser::Laser;
+use crate::math::{math, Vector2};
+use crate::sprite_component::SpriteComponent;
+
+/// Controls and tuning for the player's ship.
+#[derive(Clone)]
+pub struct ShipConfig {
+    /// Thrust, Reverse, RotateLeft, RotateRight and Fire bindings.
+    pub input_map: InputMap,
+    /// Top speeds. In drift mode they cap the velocity instead.
+    pub max_forward_speed: f32,
+    pub max_angular_speed: f32,
+    /// Classic drift handling: keys apply thrust and the ship keeps its
+    /// momentum, slowed down by drag.
+    pub drift: bool,
+    pub mass: f32,
+    pub forward_thrust: f32,
+    pub angular_thrust: f32,
+    pub linear_drag: f32,
+    pub angular_drag: f32,
+    /// Seconds between two lasers while the fire key is held.
+    pub fire_cooldown: f32,
+    /// Seconds a laser flies before disappearing.
+    pub laser_lifetime: f32,
+    /// Seconds the ship stays gone after being hit.
+    pub respawn_delay: f32,
+    /// Seconds after respawning during which asteroids can't hit the ship.
+    pub invulnerable_time: f32,
+    pub radius: f32,
+}
+
+impl Default for ShipConfig {
+    fn default() -> Self {
+        ShipConfig {
+            input_map: InputMap::default(),
+            max_forward_speed: 300.0,
+            max_angular_speed: math::TWO_PI,
+            drift: false,
+            mass: 1.0,
+            forward_thrust: 400.0,
+            angular_thrust: 40.0,
+            linear_drag: 0.3,
+            angular_drag: 6.0,
+            fire_cooldown: 0.5,
+            laser_lifetime: 1.0,
+            respawn_delay: 1.5,
+            invulnerable_time: 2.0,
+            radius: 20.0,
+        }
+    }
+}
+
+pub struct Ship {
+    fire_cooldown: f32,
+    laser_lifetime: f32,
+    respawn_delay: f32,
+    invulnerable_time: f32,
+    spawn_point: Vector2,
+    input: ComponentId,
+    sprite: ComponentId,
+    circle: ComponentId,
+    laser_cooldown: f32,
+    respawn_timer: f32,
+    invulnerable_timer: f32,
+    // Whether thrust was held last frame, to play its sound once per press
+    thrusting: bool,
+}
+
+impl Ship {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(game: &mut Game, config: ShipConfig) -> ActorId {
+        let actor = Actor::new(game);
+
+        // Create a sprite component
+        let sprite = SpriteComponent::new(game, actor, 150);
+        if let Some(texture) = game.get_texture("Assets/Ship.png") {
+            if let Some(sprite_component) = game.get_component_mut::<SpriteComponent>(sprite) {
+                sprite_component.set_texture(texture);
+            }
+        }
+
+        // Create an input component and set bindings/speeds
+        let input = InputComponent::new(game, actor, 10);
+        if let Some(input_component) = game.get_component_mut::<InputComponent>(input) {
+            input_component.set_input_map(config.input_map.clone());
+            input_component.set_max_forward_speed(config.max_forward_speed);
+            input_component.set_max_angular_speed(config.max_angular_speed);
+            input_component.set_forward_thrust(config.forward_thrust);
+            input_component.set_angular_thrust(config.angular_thrust);
+
+            let move_component = input_component.get_move_component_mut();
+            move_component.set_physics_enabled(config.drift);
+            move_component.set_mass(config.mass);
+            move_component.set_linear_drag(config.linear_drag);
+            move_component.set_angular_drag(config.angular_drag);
+            move_component.set_max_speed(config.max_forward_speed);
+            move_component.set_max_angular_speed(config.max_angular_speed);
+        }
+
+        // Create a circle component (for collision)
+        let circle = CircleComponent::new(game, actor);
+        if let Some(circle_component) = game.get_component_mut::<CircleComponent>(circle) {
+            circle_component.set_radius(config.radius);
+            circle_component.set_layer(layers::SHIP);
+            circle_component.set_mask(layers::ASTEROID);
+        }
+
+        // Start in the middle of the world
+        let spawn_point = game.get_world_bounds().get_center();
+        if let Some(ship_actor) = game.get_actor_mut(actor) {
+            ship_actor.set_position(spawn_point);
+            ship_actor.set_behavior(Box::new(Ship {
+                fire_cooldown: config.fire_cooldown,
+                laser_lifetime: config.laser_lifetime,
+                respawn_delay: config.respawn_delay,
+                invulnerable_time: config.invulnerable_time,
+                spawn_point,
+                input,
+                sprite,
+                circle,
+                laser_cooldown: 0.0,
+                respawn_timer: 0.0,
+                invulnerable_timer: 0.0,
+                thrusting: false,
+            }));
+        }
+
+        actor
+    }
+
+    pub fn is_respawning(&self) -> bool {
+        self.respawn_timer > 0.0
+    }
+
+    pub fn is_invulnerable(&self) -> bool {
+        self.invulnerable_timer > 0.0
+    }
+
+    fn hit_by_asteroid(&self, game: &Game) -> bool {
+        game.get_collision_world()
+            .get_contacts(self.circle)
+            .any(|other| game.get_asteroids().iter().any(|asteroid| asteroid.get_collider() == other))
+    }
+
+    // Blow up: disappear at the spawn point until the respawn delay is over
+    fn destroy(&mut self, game: &mut Game, id: ActorId) {
+        self.respawn_timer = self.respawn_delay;
+        game.play_sound("Assets/Explosion.wav");
+        if let Some(ship_actor) = game.get_actor_mut(id) {
+            ship_actor.set_position(self.spawn_point);
+            ship_actor.set_rotation(0.0);
+            ship_actor.reset_interpolation();
+        }
+        self.set_controllable(game, false);
+    }
+
+    fn respawn(&mut self, game: &mut Game) {
+        self.invulnerable_timer = self.invulnerable_time;
+        self.set_controllable(game, true);
+    }
+
+    fn set_controllable(&self, game: &mut Game, controllable: bool) {
+        if let Some(sprite_component) = game.get_component_mut::<SpriteComponent>(self.sprite) {
+            sprite_component.set_visible(controllable);
+        }
+        if let Some(input_component) = game.get_component_mut::<InputComponent>(self.input) {
+            input_component.set_enabled(controllable);
+        }
+    }
+}
+
+impl ActorBehavior for Ship {
+    fn update_actor(&mut self, game: &mut Game, id: ActorId, delta_time: f32) {
+        self.laser_cooldown -= delta_time;
+
+        if self.is_respawning() {
+            self.respawn_timer -= delta_time;
+            if !self.is_respawning() {
+                self.respawn(game);
+            }
+            return;
+        }
+
+        if self.is_invulnerable() {
+            self.invulnerable_timer -= delta_time;
+            // Blink while invulnerable
+            let visible = !self.is_invulnerable() || (self.invulnerable_timer * 10.0) as i32 % 2 == 0;
+            if let Some(sprite_component) = game.get_component_mut::<SpriteComponent>(self.sprite) {
+                sprite_component.set_visible(visible);
+            }
+        } else if self.hit_by_asteroid(game) {
+            self.destroy(game, id);
+        }
+    }
+
+    fn actor_input(&mut self, game: &mut Game, id: ActorId, input: &InputState) {
+        let is_down = |action| {
+            game.get_component::<InputComponent>(self.input)
+                .is_some_and(|input_component| input_component.is_action_down(action, input))
+        };
+        let fire = is_down(Action::Fire);
+        let thrusting = !self.is_respawning() && is_down(Action::Thrust);
+        if thrusting && !self.thrusting {
+            game.play_sound("Assets/Thrust.wav");
+        }
+        self.thrusting = thrusting;
+
+        if self.is_respawning() || !fire || self.laser_cooldown > 0.0 {
+            return;
+        }
+
+        // Create a laser at the ship's position and heading
+        if let Some(ship_actor) = game.get_actor(id) {
+            let position = ship_actor.get_position();
+            let rotation = ship_actor.get_rotation();
+            Laser::new(game, position, rotation, self.laser_lifetime);
+            game.play_sound("Assets/Laser.wav");
+            self.laser_cooldown = self.fire_cooldown;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asteroid::Asteroid;
+    use crate::audio::Sound;
+    use crate::game::GameConfig;
+    use crate::input_map::Binding;
+    use sdl2::controller::Axis;
+    use sdl2::keyboard::Scancode;
+
+    fn ship_behavior(game: &Game, ship: ActorId) -> &Ship {
+        game.get_actor(ship).unwrap().get_behavior::<Ship>().unwrap()
+    }
+
+    #[test]
+    fn fire_key_spawns_lasers_after_cooldown() {
+        let mut game = Game::with_config(GameConfig::headless());
+        let config = ShipConfig::default();
+        let fire_cooldown = config.fire_cooldown;
+        let ship = Ship::new(&mut game, config);
+
+        let mut input = InputState::new();
+        input.set_key(Scancode::Space, true);
+        game.process_actor_input(&input);
+        game.update_game(0.01);
+        assert_eq!(game.get_actor_ids().len(), 2);
+
+        // Still cooling down
+        game.process_actor_input(&input);
+        game.update_game(0.01);
+        assert_eq!(game.get_actor_ids().len(), 2);
+
+        game.update_game(fire_cooldown);
+        game.process_actor_input(&input);
+        game.update_game(0.01);
+        assert_eq!(game.get_actor_ids().len(), 3);
+        assert!(game.get_actor(ship).is_some());
+    }
+
+    #[test]
+    fn firing_and_thrusting_play_their_sounds() {
+        let mut game = Game::with_config(GameConfig::headless());
+        let beep = || Sound::from_samples(&[0.5; 4410], 1, 44100);
+        game.add_sound("Assets/Laser.wav", beep());
+        game.add_sound("Assets/Thrust.wav", beep());
+        Ship::new(&mut game, ShipConfig::default());
+
+        let mut input = InputState::new();
+        input.set_key(Scancode::Space, true);
+        input.set_key(Scancode::W, true);
+        game.process_actor_input(&input);
+        assert_eq!(game.get_audio().with_mixer(|mixer| mixer.get_voice_count()), 2);
+
+        // Holding thrust doesn't restart its sound
+        game.process_actor_input(&input);
+        assert_eq!(game.get_audio().with_mixer(|mixer| mixer.get_voice_count()), 2);
+
+        // Both are done after their 0.1s
+        game.update_game(0.2);
+        assert_eq!(game.get_audio().with_mixer(|mixer| mixer.get_voice_count()), 0);
+    }
+
+    #[test]
+    fn drifting_ship_keeps_moving_after_thrust_is_released() {
+        let mut game = Game::with_config(GameConfig::headless());
+        let ship = Ship::new(&mut game, ShipConfig {
+            drift: true,
+            linear_drag: 0.0,
+            ..ShipConfig::default()
+        });
+
+        let mut input = InputState::new();
+        input.set_key(Scancode::W, true);
+        game.process_actor_input(&input);
+        // Thrust keeps pushing on every step until the next input
+        game.update_game(0.1);
+        game.update_game(0.1);
+        let after_thrust = game.get_actor(ship).unwrap().get_position().x;
+        assert!(after_thrust > 0.0);
+
+        input.set_key(Scancode::W, false);
+        game.process_actor_input(&input);
+        game.update_game(0.1);
+        let drifted = game.get_actor(ship).unwrap().get_position().x;
+        // 400 * 0.2 = 80 units/s reached, kept without drag
+        assert!((drifted - after_thrust - 8.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn asteroid_hit_respawns_ship_with_invulnerability() {
+        let mut game = Game::with_config(GameConfig::headless());
+        let config = ShipConfig::default();
+        let respawn_delay = config.respawn_delay;
+        let ship = Ship::new(&mut game, config);
+        let asteroid = Asteroid::new(&mut game);
+        if let Some(asteroid_actor) = game.get_actor_mut(asteroid.get_actor()) {
+            asteroid_actor.set_position(Vector2::zero());
+        }
+
+        game.update_game(0.01);
+        let behavior = ship_behavior(&game, ship);
+        assert!(behavior.is_respawning());
+        let sprite = behavior.sprite;
+        assert!(!game.get_component::<SpriteComponent>(sprite).unwrap().is_visible());
+
+        // Firing does nothing while the ship is gone
+        let mut input = InputState::new();
+        input.set_key(Scancode::Space, true);
+        game.process_actor_input(&input);
+        game.update_game(respawn_delay);
+        assert_eq!(game.get_actor_ids().len(), 2);
+
+        // Back, and the asteroid still on top of it can't hit it again
+        let behavior = ship_behavior(&game, ship);
+        assert!(!behavior.is_respawning());
+        assert!(behavior.is_invulnerable());
+        game.update_game(0.01);
+        assert!(!ship_behavior(&game, ship).is_respawning());
+        assert!(game.get_actor(asteroid.get_actor()).is_some());
+    }
+
+    #[test]
+    fn rebound_fire_and_half_stick_turn() {
+        let mut game = Game::with_config(GameConfig::headless());
+        let mut config = ShipConfig::default();
+        config.input_map.rebind(Action::Fire, Binding::Key(Scancode::Space), Binding::Key(Scancode::LCtrl));
+        config.input_map.set_deadzone(0.0);
+        let max_angular_speed = config.max_angular_speed;
+        let ship = Ship::new(&mut game, config);
+
+        let mut input = InputState::new();
+        input.set_key(Scancode::Space, true);
+        input.set_gamepad_axis(Axis::LeftX, -0.5);
+        game.process_actor_input(&input);
+        game.update_game(0.1);
+        assert_eq!(game.get_actor_ids().len(), 1);
+        let rotation = game.get_actor(ship).unwrap().get_rotation();
+        assert!((rotation - 0.5 * max_angular_speed * 0.1).abs() < 1e-4);
+
+        input.set_key(Scancode::LCtrl, true);
+        game.process_actor_input(&input);
+        game.update_game(0.01);
+        assert_eq!(game.get_actor_ids().len(), 2);
+    }
+}
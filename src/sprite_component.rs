@@ -1,36 +1,49 @@
-use crate::actor::Actor;
+use crate::actor::ActorId;
+use crate::component::{Component, BaseComponent, ComponentId};
+use crate::game::Game;
 use crate::shader::Shader;
 use crate::texture::Texture;
 use crate::math::Matrix4;
+use crate::vertex_array::VertexArray;
 use std::rc::Rc;
 use std::cell::RefCell;
 
 #[derive(Clone)]
 pub struct SpriteComponent {
-    owner: Rc<RefCell<Actor>>,
+    base: BaseComponent,
     texture: Option<Rc<RefCell<Texture>>>,
     draw_order: i32,
     tex_width: i32,
     tex_height: i32,
+    visible: bool,
 }
 
 impl SpriteComponent {
-    pub fn new(owner: Rc<RefCell<Actor>>, draw_order: i32) -> Self {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(game: &mut Game, owner: ActorId, draw_order: i32) -> ComponentId {
         let sprite_component = Self {
-            owner,
+            base: BaseComponent::new(owner, draw_order),
             texture: None,
             draw_order,
             tex_width: 0,
             tex_height: 0,
+            visible: true,
         };
 
-        let game = sprite_component.owner.borrow_mut().get_game();
-        game.borrow_mut().add_sprite(Rc::new(RefCell::new(sprite_component.clone())));
-
-        sprite_component
+        let id = game.add_component(Box::new(sprite_component));
+        game.add_sprite(id);
+        id
     }
 
-    pub fn draw(&self, shader: &Shader) {
+    pub fn draw(&self, game: &Game, shader: &Shader, verts: &VertexArray) {
+        let Some(owner) = game.get_actor(self.base.owner) else {
+            return;
+        };
+
+        if !self.visible {
+            return;
+        }
+
         if let Some(texture) = &self.texture {
             // テクスチャの幅と高さに基づいてスケーリング行列を生成
             let scale_mat = Matrix4::create_scale(
@@ -40,7 +53,8 @@ impl SpriteComponent {
             );
 
             // ワールド変換行列を取得してスケーリング行列と掛け合わせる
-            let world = scale_mat * self.owner.borrow_mut().get_world_transform();
+            // (前回のステップとの間を補間)
+            let world = scale_mat * owner.get_interpolated_world_transform(game.get_interpolation_alpha());
 
             // シェーダーにワールド変換行列を設定
             shader.set_matrix_uniform("uWorldTransform", &world);
@@ -50,7 +64,12 @@ impl SpriteComponent {
 
             // 四角形を描画
             unsafe {
-                gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, std::ptr::null());
+                gl::DrawElements(
+                    gl::TRIANGLES,
+                    verts.get_num_indices() as i32,
+                    gl::UNSIGNED_INT,
+                    std::ptr::null(),
+                );
             }
         }
     }
@@ -72,11 +91,24 @@ impl SpriteComponent {
     pub fn get_tex_width(&self) -> i32 {
         self.tex_width
     }
+
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
 }
 
-impl Drop for SpriteComponent {
-    fn drop(&mut self) {
-        let sprite_component = Rc::new(RefCell::new(self.clone()));
-        self.owner.borrow_mut().get_game().borrow_mut().remove_sprite(sprite_component);
+impl Component for SpriteComponent {
+    fn update(&mut self, _game: &mut Game, _delta_time: f32) {}
+
+    fn get_update_order(&self) -> i32 {
+        self.base.get_update_order()
+    }
+
+    fn get_owner(&self) -> ActorId {
+        self.base.get_owner()
     }
 }
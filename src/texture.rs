@@ -1,8 +1,5 @@
-use std::path::Path;
-use sdl2::render::TextureCreator;
-use sdl2::video::WindowContext;
-use sdl2::image::LoadTexture;
 use gl::types::*;
+use image::RgbaImage;
 
 #[derive(Clone)]
 pub struct Texture {
@@ -20,25 +17,28 @@ impl Texture {
         }
     }
 
-    pub fn load(
-        &mut self,
-        texture_creator: &TextureCreator<WindowContext>,
-        file_name: &str,
-    ) -> Result<(), String> {
-        let sdl_texture = texture_creator.load_texture(Path::new(file_name))?;
-        let query = sdl_texture.query();
-
-        self.width = query.width;
-        self.height = query.height;
+    /// Build a texture from raw RGBA8 pixels, rows top to bottom. Needs a
+    /// current GL context.
+    pub fn from_rgba(width: u32, height: u32, pixels: &[u8]) -> Result<Self, String> {
+        let mut texture = Texture::new();
+        texture.upload_rgba(width, height, pixels)?;
+        Ok(texture)
+    }
 
-        unsafe {
-            gl::GenTextures(1, &mut self.texture_id);
-            gl::BindTexture(gl::TEXTURE_2D, self.texture_id);
-            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as GLint);
-            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as GLint);
-        }
+    /// Build a texture from an encoded image (PNG, JPEG, ...) held in memory,
+    /// e.g. one embedded with `include_bytes!`. Needs a current GL context.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let image = image::load_from_memory(bytes)
+            .map_err(|err| format!("Failed to decode texture: {}", err))?;
+        let mut texture = Texture::new();
+        texture.upload_image(&image.to_rgba8())?;
+        Ok(texture)
+    }
 
-        Ok(())
+    pub fn load(&mut self, file_name: &str) -> Result<(), String> {
+        let image = image::open(file_name)
+            .map_err(|err| format!("Failed to load texture {}: {}", file_name, err))?;
+        self.upload_image(&image.to_rgba8())
     }
 
     pub fn unload(&mut self) {
@@ -63,4 +63,71 @@ impl Texture {
     pub fn get_height(&self) -> u32 {
         self.height
     }
+
+    fn upload_image(&mut self, image: &RgbaImage) -> Result<(), String> {
+        self.upload_rgba(image.width(), image.height(), image.as_raw())
+    }
+
+    fn upload_rgba(&mut self, width: u32, height: u32, pixels: &[u8]) -> Result<(), String> {
+        let expected_len = width as usize * height as usize * 4;
+        if pixels.len() != expected_len {
+            return Err(format!(
+                "Texture data is {} bytes, expected {} for {}x{} RGBA",
+                pixels.len(),
+                expected_len,
+                width,
+                height
+            ));
+        }
+
+        // Loading over an existing texture replaces it
+        self.unload();
+        self.width = width;
+        self.height = height;
+
+        unsafe {
+            gl::GenTextures(1, &mut self.texture_id);
+            gl::BindTexture(gl::TEXTURE_2D, self.texture_id);
+            gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+            gl::TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                gl::RGBA as GLint,
+                width as GLsizei,
+                height as GLsizei,
+                0,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                pixels.as_ptr() as *const _,
+            );
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as GLint);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as GLint);
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Texture {
+    fn default() -> Self {
+        Texture::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Both errors come back before any GL call, so no context is needed
+    #[test]
+    fn pixel_count_must_match_the_size() {
+        let err = Texture::from_rgba(2, 2, &[255; 15]).err().unwrap();
+        assert_eq!(err, "Texture data is 15 bytes, expected 16 for 2x2 RGBA");
+    }
+
+    #[test]
+    fn undecodable_bytes_are_an_error() {
+        let err = Texture::from_bytes(b"not an image").err().unwrap();
+        assert!(err.starts_with("Failed to decode texture"), "{}", err);
+    }
 }
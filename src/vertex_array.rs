@@ -0,0 +1,85 @@
+use gl::types::*;
+use std::mem::{size_of, size_of_val};
+
+// Interleaved vertex layout: position (x, y, z) then texture coordinates (u, v)
+const FLOATS_PER_VERTEX: usize = 5;
+
+pub struct VertexArray {
+    num_indices: u32,
+    vertex_buffer: u32,
+    index_buffer: u32,
+    vertex_array: u32,
+}
+
+impl VertexArray {
+    pub fn new(verts: &[f32], indices: &[u32]) -> Self {
+        let mut vertex_array = VertexArray {
+            num_indices: indices.len() as u32,
+            vertex_buffer: 0,
+            index_buffer: 0,
+            vertex_array: 0,
+        };
+
+        unsafe {
+            // 頂点配列オブジェクトを作成
+            gl::GenVertexArrays(1, &mut vertex_array.vertex_array);
+            gl::BindVertexArray(vertex_array.vertex_array);
+
+            // 頂点バッファを作成
+            gl::GenBuffers(1, &mut vertex_array.vertex_buffer);
+            gl::BindBuffer(gl::ARRAY_BUFFER, vertex_array.vertex_buffer);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                size_of_val(verts) as GLsizeiptr,
+                verts.as_ptr() as *const _,
+                gl::STATIC_DRAW,
+            );
+
+            // インデックスバッファを作成
+            gl::GenBuffers(1, &mut vertex_array.index_buffer);
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, vertex_array.index_buffer);
+            gl::BufferData(
+                gl::ELEMENT_ARRAY_BUFFER,
+                size_of_val(indices) as GLsizeiptr,
+                indices.as_ptr() as *const _,
+                gl::STATIC_DRAW,
+            );
+
+            // 頂点属性を指定 (位置とテクスチャ座標)
+            let stride = (FLOATS_PER_VERTEX * size_of::<f32>()) as GLsizei;
+            gl::EnableVertexAttribArray(0);
+            gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, stride, std::ptr::null());
+            gl::EnableVertexAttribArray(1);
+            gl::VertexAttribPointer(
+                1,
+                2,
+                gl::FLOAT,
+                gl::FALSE,
+                stride,
+                (3 * size_of::<f32>()) as *const _,
+            );
+        }
+
+        vertex_array
+    }
+
+    pub fn set_active(&self) {
+        unsafe {
+            gl::BindVertexArray(self.vertex_array);
+        }
+    }
+
+    pub fn get_num_indices(&self) -> u32 {
+        self.num_indices
+    }
+}
+
+impl Drop for VertexArray {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteBuffers(1, &self.vertex_buffer);
+            gl::DeleteBuffers(1, &self.index_buffer);
+            gl::DeleteVertexArrays(1, &self.vertex_array);
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use crate::math::{Matrix4, Vector2, Vector3};
+
+/// Axis-aligned rectangle the game world lives in. Movement edge policies
+/// and the sprite view-projection both work from it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorldBounds {
+    min: Vector2,
+    max: Vector2,
+}
+
+impl WorldBounds {
+    pub fn new(min: Vector2, max: Vector2) -> Self {
+        WorldBounds { min, max }
+    }
+
+    // Centered on the origin, the way the window is mapped to the world
+    pub fn from_size(width: f32, height: f32) -> Self {
+        WorldBounds::new(
+            Vector2::new(-width / 2.0, -height / 2.0),
+            Vector2::new(width / 2.0, height / 2.0),
+        )
+    }
+
+    pub fn get_min(&self) -> Vector2 {
+        self.min
+    }
+
+    pub fn get_max(&self) -> Vector2 {
+        self.max
+    }
+
+    pub fn get_width(&self) -> f32 {
+        self.max.x - self.min.x
+    }
+
+    pub fn get_height(&self) -> f32 {
+        self.max.y - self.min.y
+    }
+
+    pub fn get_center(&self) -> Vector2 {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn contains(&self, point: Vector2) -> bool {
+        point.x >= self.min.x && point.x <= self.max.x && point.y >= self.min.y && point.y <= self.max.y
+    }
+
+    // Maps the bounds onto the whole viewport
+    pub fn get_view_proj(&self) -> Matrix4 {
+        let center = self.get_center();
+        Matrix4::create_translation(Vector3::new(-center.x, -center.y, 0.0))
+            * Matrix4::create_simple_view_proj(self.get_width(), self.get_height())
+    }
+}